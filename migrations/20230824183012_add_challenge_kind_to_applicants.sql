@@ -0,0 +1 @@
+ALTER TABLE applicants ADD COLUMN challenge_kind varchar NOT NULL DEFAULT 'barcode';
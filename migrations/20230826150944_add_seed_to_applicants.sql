@@ -0,0 +1,3 @@
+-- Rows created before seeded generation cannot be reproduced, so both columns stay nullable.
+ALTER TABLE applicants ADD COLUMN seed bigint;
+ALTER TABLE applicants ADD COLUMN generator_version integer;
@@ -0,0 +1,5 @@
+-- Submissions recorded before per-case grading only know whether they were correct.
+ALTER TABLE submissions ADD COLUMN cases_passed integer;
+ALTER TABLE submissions ADD COLUMN cases_total integer;
+ALTER TABLE submissions ADD COLUMN failed_cases integer[];
+ALTER TABLE submissions ADD COLUMN score double precision;
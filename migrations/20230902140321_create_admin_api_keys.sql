@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS admin_api_keys (
+    key_hash varchar PRIMARY KEY,
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    revoked_at timestamp with time zone
+);
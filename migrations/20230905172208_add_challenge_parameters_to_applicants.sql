@@ -0,0 +1,2 @@
+-- An empty object stands for the parameters every earlier challenge was generated with.
+ALTER TABLE applicants ADD COLUMN challenge_parameters jsonb NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+-- Applicants registered before deadlines existed have none.
+ALTER TABLE applicants ADD COLUMN deadline timestamp with time zone;
@@ -0,0 +1,9 @@
+-- Applicants registered before token recovery was verified have no email on file.
+ALTER TABLE applicants ADD COLUMN email varchar;
+
+CREATE TABLE IF NOT EXISTS recovery_codes (
+    nuid varchar PRIMARY KEY REFERENCES applicants (nuid),
+    code_hash varchar NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    failed_attempts integer NOT NULL DEFAULT 0
+);
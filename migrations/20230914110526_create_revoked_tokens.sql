@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    token uuid PRIMARY KEY,
+    nuid varchar NOT NULL REFERENCES applicants (nuid),
+    revoked_at timestamp with time zone NOT NULL DEFAULT now(),
+    -- Either 'applicant' or 'admin'.
+    revoked_by varchar NOT NULL,
+    -- The admin API key that revoked the token, if any.
+    admin_key_hash varchar REFERENCES admin_api_keys (key_hash)
+);
+
+CREATE INDEX IF NOT EXISTS revoked_tokens_nuid_idx ON revoked_tokens (nuid);
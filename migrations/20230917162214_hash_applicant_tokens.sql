@@ -0,0 +1,9 @@
+-- Tokens are stored as keyed hashes. The key lives in configuration, so rows
+-- holding a plaintext token are hashed, and the plaintext cleared, at startup.
+ALTER TABLE applicants ADD COLUMN token_hash varchar UNIQUE;
+ALTER TABLE applicants ALTER COLUMN token DROP NOT NULL;
+
+ALTER TABLE revoked_tokens DROP CONSTRAINT revoked_tokens_pkey;
+ALTER TABLE revoked_tokens ADD COLUMN revoked_token_id serial PRIMARY KEY;
+ALTER TABLE revoked_tokens ADD COLUMN token_hash varchar UNIQUE;
+ALTER TABLE revoked_tokens ALTER COLUMN token DROP NOT NULL;
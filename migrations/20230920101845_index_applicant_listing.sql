@@ -0,0 +1,4 @@
+-- Backs the reviewer listing: filtering by registration time and finding
+-- each applicant's latest submission.
+CREATE INDEX IF NOT EXISTS applicants_registration_time_idx ON applicants (registration_time, nuid);
+CREATE INDEX IF NOT EXISTS submissions_nuid_submission_time_idx ON submissions (nuid, submission_time DESC);
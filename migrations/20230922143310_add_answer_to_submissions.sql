@@ -0,0 +1,3 @@
+-- The submitted answer, so reviewers can see every attempt. Submissions made
+-- before it was recorded have none.
+ALTER TABLE submissions ADD COLUMN answer text[];
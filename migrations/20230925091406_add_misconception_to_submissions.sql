@@ -0,0 +1,2 @@
+-- The known bug that best explains an incorrect submission, for reviewers.
+ALTER TABLE submissions ADD COLUMN misconception varchar;
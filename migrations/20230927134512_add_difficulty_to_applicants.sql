@@ -0,0 +1,2 @@
+-- Applicants registered before difficulty tiers were all issued the standard one.
+ALTER TABLE applicants ADD COLUMN difficulty varchar NOT NULL DEFAULT 'standard';
@@ -0,0 +1,2 @@
+-- Submissions accepted after the deadline, during the configured grace period.
+ALTER TABLE submissions ADD COLUMN late boolean NOT NULL DEFAULT false;
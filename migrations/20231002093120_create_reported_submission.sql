@@ -0,0 +1,15 @@
+-- The submission a scoring policy reports for an applicant, shared by every
+-- query that reports one. The policy is the snake_case name of a
+-- `ScoringPolicy`. Being a single STABLE SELECT, it is inlined into callers.
+CREATE OR REPLACE FUNCTION reported_submission(applicant varchar, policy text)
+RETURNS SETOF submissions
+LANGUAGE sql STABLE
+AS $$
+    SELECT * FROM submissions WHERE nuid = applicant ORDER BY
+    CASE policy WHEN 'first_correct' THEN correct::int
+        WHEN 'best_score' THEN COALESCE(score, correct::int) ELSE 0 END DESC,
+    CASE WHEN policy = 'latest' OR (policy = 'first_correct' AND NOT correct)
+        THEN submission_time END DESC,
+    submission_time
+    LIMIT 1
+$$;
@@ -0,0 +1,169 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use actix_web::http::header;
+use actix_web::{web, FromRequest, HttpRequest};
+use secrecy::{ExposeSecret, Secret};
+use sha2::{Digest, Sha256};
+use sqlx::{query, PgPool};
+
+use crate::configuration::AdminSettings;
+use crate::domain::ApplicantToken;
+use crate::errors::ApiError;
+
+/// The key applicant tokens are hashed with before they are stored.
+#[derive(Clone)]
+pub struct HmacSecret(pub Secret<String>);
+
+/// Proof that the request carried a valid, unrevoked admin API key.
+pub struct AdminUser {
+    pub key_hash: String,
+}
+
+impl FromRequest for AdminUser {
+    type Error = ApiError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        let api_key = bearer_token(req);
+        let pool = req.app_data::<web::Data<PgPool>>().cloned();
+
+        Box::pin(async move {
+            let api_key = api_key?;
+            let pool = pool.ok_or_else(|| {
+                ApiError::Unexpected("Database pool is not configured.".to_string())
+            })?;
+            validate_api_key(&pool, &api_key).await
+        })
+    }
+}
+
+fn invalid_api_key() -> ApiError {
+    ApiError::Unauthorized("Invalid admin API key!".to_string())
+}
+
+fn bearer_token(req: &HttpRequest) -> Result<Secret<String>, ApiError> {
+    let header_value = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .ok_or_else(|| {
+            ApiError::Unauthorized(
+                "Missing admin API key! Use the Authorization: Bearer header.".to_string(),
+            )
+        })?
+        .to_str()
+        .map_err(|_| invalid_api_key())?;
+
+    match header_value.strip_prefix("Bearer ") {
+        Some(token) if !token.trim().is_empty() => Ok(Secret::new(token.trim().to_string())),
+        _ => Err(invalid_api_key()),
+    }
+}
+
+pub fn hash_api_key(api_key: &Secret<String>) -> String {
+    format!("{:x}", Sha256::digest(api_key.expose_secret().as_bytes()))
+}
+
+#[tracing::instrument(name = "Validating admin API key.", skip(pool, api_key))]
+async fn validate_api_key(pool: &PgPool, api_key: &Secret<String>) -> Result<AdminUser, ApiError> {
+    let key_hash = hash_api_key(api_key);
+
+    let record = query!(
+        r#"SELECT revoked_at FROM admin_api_keys WHERE key_hash=$1"#,
+        &key_hash
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    match record {
+        Some(record) if record.revoked_at.is_none() => Ok(AdminUser { key_hash }),
+        Some(_) => Err(ApiError::Forbidden(
+            "Admin API key has been revoked!".to_string(),
+        )),
+        None => Err(invalid_api_key()),
+    }
+}
+
+/// Makes the configured admin API keys the only active ones, revoking any key
+/// that has been removed from configuration.
+#[tracing::instrument(name = "Storing admin API keys.", skip(pool, admin_settings))]
+pub async fn store_admin_api_keys(
+    pool: &PgPool,
+    admin_settings: &AdminSettings,
+) -> Result<(), sqlx::Error> {
+    let key_hashes = admin_settings
+        .api_keys
+        .iter()
+        .map(hash_api_key)
+        .collect::<Vec<String>>();
+
+    let mut transaction = pool.begin().await?;
+
+    query!(
+        r#"INSERT INTO admin_api_keys (key_hash) SELECT * FROM UNNEST($1::varchar[])
+        ON CONFLICT (key_hash) DO UPDATE SET revoked_at = NULL;"#,
+        &key_hashes
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    query!(
+        r#"UPDATE admin_api_keys SET revoked_at = now()
+        WHERE revoked_at IS NULL AND NOT (key_hash = ANY($1));"#,
+        &key_hashes
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await
+}
+
+/// Replaces every plaintext applicant token left from before tokens were
+/// hashed with its keyed hash, returning how many were replaced.
+#[tracing::instrument(name = "Hashing stored applicant tokens.", skip(pool, hmac_secret))]
+pub async fn hash_stored_tokens(
+    pool: &PgPool,
+    hmac_secret: &HmacSecret,
+) -> Result<u64, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    let mut hashed = 0;
+
+    let applicants = query!(
+        r#"SELECT nuid, token AS "token!" FROM applicants WHERE token IS NOT NULL FOR UPDATE"#
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    for applicant in applicants {
+        query!(
+            r#"UPDATE applicants SET token_hash=$2, token=NULL WHERE nuid=$1"#,
+            applicant.nuid,
+            ApplicantToken::from(applicant.token).hash(&hmac_secret.0)
+        )
+        .execute(&mut *transaction)
+        .await?;
+        hashed += 1;
+    }
+
+    let revoked_tokens = query!(
+        r#"SELECT revoked_token_id, token AS "token!" FROM revoked_tokens
+        WHERE token IS NOT NULL FOR UPDATE"#
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    for revoked_token in revoked_tokens {
+        query!(
+            r#"UPDATE revoked_tokens SET token_hash=$2, token=NULL WHERE revoked_token_id=$1"#,
+            revoked_token.revoked_token_id,
+            ApplicantToken::from(revoked_token.token).hash(&hmac_secret.0)
+        )
+        .execute(&mut *transaction)
+        .await?;
+        hashed += 1;
+    }
+
+    transaction.commit().await?;
+
+    Ok(hashed)
+}
@@ -1,13 +1,24 @@
+use crate::domain::{AttemptPolicy, ChallengeKind, ChallengeKindName, Difficulty, ScoringPolicy};
 use secrecy::{ExposeSecret, Secret};
 use serde_aux::field_attributes::deserialize_number_from_string;
 use sqlx::postgres::{PgConnectOptions, PgSslMode};
 use sqlx::ConnectOptions;
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
+use strum::IntoEnumIterator;
 
 #[derive(serde::Deserialize, Clone, Debug)]
 pub struct Settings {
     pub database: DatabaseSettings,
     pub application: ApplicationSettings,
+    pub challenge: ChallengeSettings,
+    pub admin: AdminSettings,
+    pub submission: AttemptPolicy,
+    pub rate_limit: RateLimitSettings,
+    pub email: EmailSettings,
+    pub recovery: RecoverySettings,
+    pub registration: RegistrationSettings,
+    pub scoring: ScoringSettings,
 }
 
 #[derive(serde::Deserialize, Clone, Debug)]
@@ -16,6 +27,146 @@ pub struct ApplicationSettings {
     pub port: u16,
     pub host: String,
     pub base_url: String,
+    /// Key applicant tokens are hashed with. Changing it invalidates every token.
+    pub hmac_secret: Secret<String>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct ChallengeSettings {
+    pub kind: ChallengeKindName,
+    /// Kind specific generation parameters, e.g. case counts and lengths.
+    pub parameters: serde_json::Value,
+    /// Parameters each difficulty overrides, replacing whole top level fields.
+    pub difficulties: HashMap<Difficulty, serde_json::Value>,
+    /// The difficulty of applicants who do not pick one.
+    pub default_difficulty: Difficulty,
+    /// Difficulties applicants may ask for when registering, besides the
+    /// default. Empty, so reviewers alone decide, unless configured.
+    pub selectable_difficulties: Vec<Difficulty>,
+    /// How long after registering an applicant may fetch and submit their challenge.
+    pub time_limit_hours: u32,
+    /// How long after the deadline submissions are still accepted, flagged as late.
+    pub late_grace_minutes: u32,
+}
+
+impl ChallengeSettings {
+    pub fn challenge_kind(&self, difficulty: Difficulty) -> Result<Box<dyn ChallengeKind>, String> {
+        self.kind.challenge_kind(&self.parameters(difficulty)?)
+    }
+
+    pub fn parameters(&self, difficulty: Difficulty) -> Result<serde_json::Value, String> {
+        let overrides = self.difficulties.get(&difficulty).ok_or_else(|| {
+            format!(
+                "No parameters configured for difficulty! Given: {}",
+                difficulty
+            )
+        })?;
+
+        let mut parameters = self.parameters.clone();
+        if let (Some(parameters), Some(overrides)) =
+            (parameters.as_object_mut(), overrides.as_object())
+        {
+            parameters.extend(overrides.clone());
+        }
+
+        Ok(parameters)
+    }
+
+    /// The difficulty to issue an applicant who asked for `requested`.
+    pub fn difficulty(&self, requested: Option<Difficulty>) -> Result<Difficulty, String> {
+        match requested {
+            None => Ok(self.default_difficulty),
+            Some(difficulty)
+                if difficulty == self.default_difficulty
+                    || self.selectable_difficulties.contains(&difficulty) =>
+            {
+                Ok(difficulty)
+            }
+            Some(difficulty) => Err(format!(
+                "Difficulty cannot be chosen! Given: {}",
+                difficulty
+            )),
+        }
+    }
+
+    pub fn time_limit(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.time_limit_hours.into())
+    }
+
+    pub fn late_grace(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.late_grace_minutes.into())
+    }
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct AdminSettings {
+    pub api_keys: Vec<Secret<String>>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct RegistrationSettings {
+    /// Email domains applicants may register with, subdomains included.
+    /// Empty allows any domain.
+    pub allowed_email_domains: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct ScoringSettings {
+    /// Policy used when a reviewer does not pick one.
+    pub default_policy: ScoringPolicy,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct RateLimitSettings {
+    /// Use the right-most `X-Forwarded-For` entry, the one a reverse proxy
+    /// appends, instead of the peer address. Only enable behind a single proxy
+    /// that appends to the header.
+    pub trust_forwarded_for: bool,
+    pub per_ip: BucketSettings,
+    pub per_nuid: BucketSettings,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct BucketSettings {
+    /// The largest burst allowed.
+    pub capacity: u32,
+    /// How long it takes to earn back a single request.
+    pub refill_seconds: u64,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct EmailSettings {
+    pub sender: String,
+    pub transport: EmailTransport,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EmailTransport {
+    Smtp {
+        host: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        port: u16,
+        username: String,
+        password: Secret<String>,
+    },
+    /// Writes emails to `directory` instead of sending them.
+    File { directory: String },
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct RecoverySettings {
+    /// How long a token recovery code stays valid once sent.
+    pub code_ttl_minutes: u32,
+    /// Wrong guesses allowed before a code is invalidated. They are counted
+    /// across reissues until the latest code expires.
+    pub max_code_attempts: u32,
+}
+
+impl RecoverySettings {
+    pub fn code_ttl(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.code_ttl_minutes.into())
+    }
 }
 
 #[derive(serde::Deserialize, Clone, Debug)]
@@ -70,11 +221,31 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
         .add_source(
             config::Environment::with_prefix("APP")
                 .prefix_separator("_")
-                .separator("__"),
+                .separator("__")
+                .try_parsing(true)
+                .list_separator(",")
+                .with_list_parse_key("admin.api_keys")
+                .with_list_parse_key("registration.allowed_email_domains")
+                .with_list_parse_key("challenge.parameters.mandatory_cases")
+                .with_list_parse_key("challenge.selectable_difficulties"),
         )
         .build()?;
 
-    settings.try_deserialize::<Settings>()
+    let settings = settings.try_deserialize::<Settings>()?;
+
+    for difficulty in Difficulty::iter() {
+        settings
+            .challenge
+            .challenge_kind(difficulty)
+            .map_err(config::ConfigError::Message)?;
+    }
+
+    settings
+        .email
+        .mailer()
+        .map_err(config::ConfigError::Message)?;
+
+    Ok(settings)
 }
 
 pub enum Environment {
@@ -1,47 +1,341 @@
+pub mod bug_zoo;
+
 use std::fmt::{Display, Formatter};
 
 use rand::{
+    distributions::{Distribution, WeightedIndex},
     seq::{IteratorRandom, SliceRandom},
-    Rng,
+    Rng, RngCore,
 };
 
 use strum::IntoEnumIterator;
 
-pub struct Challenge {
-    pub challenge: Vec<String>,
-    pub solution: Vec<String>,
+use crate::domain::ChallengeKind;
+
+pub use bug_zoo::BarcodeBug;
+
+const GENERATOR_VERSION: i32 = 1;
+
+/// The original barcode puzzle: decode a string of digits interleaved with
+/// block instructions.
+///
+/// Missing fields fall back to the parameters every challenge was issued with
+/// before they became configurable, so those defaults must not change.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Barcode {
+    pub num_random_cases: usize,
+    pub num_digits: CountRange,
+    pub num_instructions: CountRange,
+    pub mandatory_cases: Vec<String>,
+    /// Spends part of `num_random_cases` on cases catching each bug in the
+    /// bug zoo, so every challenge tells the buggy solutions apart.
+    pub adversarial_cases: bool,
+    /// How many cases catch each bug when `adversarial_cases` is set.
+    pub witnesses_per_bug: usize,
+    pub instruction_mix: InstructionMix,
+    /// Adds '>', '~', '-' and '$' to the original four instructions. Off for
+    /// every challenge issued before they existed.
+    pub extended_instructions: bool,
 }
 
-pub fn generate_challenge(n_random: usize, mandatory_cases: Vec<String>) -> Challenge {
-    let mut rng = rand::thread_rng();
-    let random_cases = (0..n_random)
-        .map(|_| generate_random_case(&mut rng))
-        .collect::<Vec<String>>();
+/// How the instructions of random cases are drawn. The default draws each
+/// instruction equally often, one per gap between digits.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct InstructionMix {
+    /// Relative odds of each instruction; uniform when absent.
+    pub weights: Option<InstructionWeights>,
+    /// Longest run of one instruction put in a single gap, e.g. "!!!" or "%%".
+    /// A run counts once toward `num_instructions`.
+    pub max_run: usize,
+}
 
-    let mut challenge = mandatory_cases;
+/// Weights of the extended instructions are only used when they are enabled.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct InstructionWeights {
+    pub beg_end: u32,
+    pub repeat: u32,
+    pub reverse: u32,
+    pub encrypt: u32,
+    #[serde(default = "default_weight")]
+    pub rotate: u32,
+    #[serde(default = "default_weight")]
+    pub swap: u32,
+    #[serde(default = "default_weight")]
+    pub drop: u32,
+    #[serde(default = "default_weight")]
+    pub checksum: u32,
+}
 
-    challenge.extend(random_cases);
+fn default_weight() -> u32 {
+    1
+}
 
-    challenge.shuffle(&mut rng);
+impl Default for InstructionMix {
+    fn default() -> Self {
+        Self {
+            weights: None,
+            max_run: 1,
+        }
+    }
+}
 
-    let solution = challenge
-        .iter()
-        .map(|case| parse_barcode(case))
-        .collect::<Vec<String>>();
+impl InstructionMix {
+    fn validate(&self, instructions: &[Instruction]) -> Result<(), String> {
+        if self.max_run == 0 {
+            return Err("Instruction runs must be at least 1 long!".to_string());
+        }
 
-    Challenge {
-        challenge,
-        solution,
+        match self.weights {
+            Some(weights)
+                if instructions
+                    .iter()
+                    .all(|&instruction| weights.weight(instruction) == 0) =>
+            {
+                Err("At least one instruction weight must be positive!".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The instructions to put in one gap. Draws nothing beyond a uniform
+    /// choice under the default mix, so challenges issued before mixes were
+    /// configurable regenerate unchanged.
+    fn choose_run(&self, rng: &mut impl Rng, instructions: &[Instruction]) -> String {
+        let instruction = match self.weights {
+            Some(weights) => WeightedIndex::new(
+                instructions
+                    .iter()
+                    .map(|&instruction| weights.weight(instruction)),
+            )
+            .ok()
+            .map(|index| instructions[index.sample(rng)]),
+            None => instructions.iter().copied().choose(rng),
+        };
+        let run = if self.max_run > 1 {
+            rng.gen_range(1..=self.max_run)
+        } else {
+            1
+        };
+
+        instruction
+            .map(|instruction| instruction.to_string().repeat(run))
+            .unwrap_or_default()
     }
 }
 
-#[derive(strum::EnumIter, Default, Debug)]
+impl InstructionWeights {
+    fn weight(&self, instruction: Instruction) -> u32 {
+        match instruction {
+            Instruction::BegEnd => self.beg_end,
+            Instruction::Repeat => self.repeat,
+            Instruction::Reverse => self.reverse,
+            Instruction::Encrypt => self.encrypt,
+            Instruction::Rotate => self.rotate,
+            Instruction::Swap => self.swap,
+            Instruction::Drop => self.drop,
+            Instruction::Checksum => self.checksum,
+        }
+    }
+}
+
+/// An inclusive range of counts. Instruction counts are capped at one fewer
+/// than the number of digits, since instructions only go between digits.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CountRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Default for Barcode {
+    fn default() -> Self {
+        Self {
+            num_random_cases: 256,
+            num_digits: CountRange { min: 32, max: 64 },
+            num_instructions: CountRange { min: 16, max: 32 },
+            mandatory_cases: vec![
+                String::from(""),
+                String::from("#12#34!#59^#67%#"),
+                String::from("#12^!%%###34^#"),
+                String::from("##"),
+            ],
+            adversarial_cases: false,
+            witnesses_per_bug: 1,
+            instruction_mix: InstructionMix::default(),
+            extended_instructions: false,
+        }
+    }
+}
+
+impl Barcode {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_random_cases == 0 && self.mandatory_cases.is_empty() {
+            return Err("A barcode challenge must contain at least one case!".to_string());
+        }
+
+        if self.num_digits.min == 0 || self.num_digits.min > self.num_digits.max {
+            return Err(format!(
+                "Invalid barcode digit range! Given: {}..={}",
+                self.num_digits.min, self.num_digits.max
+            ));
+        }
+
+        if self.num_instructions.min > self.num_instructions.max {
+            return Err(format!(
+                "Invalid barcode instruction range! Given: {}..={}",
+                self.num_instructions.min, self.num_instructions.max
+            ));
+        }
+
+        let instructions = self.instructions();
+        self.instruction_mix.validate(instructions)?;
+
+        match self.mandatory_cases.iter().find(|case| {
+            !case.chars().all(|c| {
+                c.is_ascii_digit()
+                    || Instruction::parse(&c)
+                        .is_some_and(|instruction| instructions.contains(&instruction))
+            })
+        }) {
+            Some(case) => Err(format!("Invalid mandatory barcode case! Given: {}", case)),
+            None => Ok(()),
+        }
+    }
+
+    /// The instructions cases may contain.
+    fn instructions(&self) -> &'static [Instruction] {
+        if self.extended_instructions {
+            &Instruction::EXTENDED
+        } else {
+            &Instruction::ORIGINAL
+        }
+    }
+
+    fn generate_random_case(&self, rng: &mut impl Rng) -> String {
+        random_case(
+            rng,
+            self.num_digits,
+            self.num_instructions,
+            &self.instruction_mix,
+            self.instructions(),
+        )
+    }
+}
+
+/// A case with instructions placed at random between its digits.
+fn random_case(
+    rng: &mut impl Rng,
+    num_digits: CountRange,
+    num_instructions: CountRange,
+    instruction_mix: &InstructionMix,
+    instructions: &[Instruction],
+) -> String {
+    let num_numeric = rng.gen_range(num_digits.min..=num_digits.max);
+    let num_instructions = rng.gen_range(num_instructions.min..=num_instructions.max);
+
+    let mut result = Instruction::BegEnd.to_string();
+
+    result += &(0..num_numeric)
+        .map(|_| rng.gen_range(0..=9).to_string())
+        .collect::<String>();
+
+    let mut instruction_positions: Vec<usize> = (1..num_numeric).collect();
+    instruction_positions.shuffle(rng);
+    instruction_positions.truncate(num_instructions);
+    instruction_positions.sort_unstable();
+
+    let mut inserted = 0;
+    instruction_positions.iter().for_each(|&position| {
+        let run = instruction_mix.choose_run(rng, instructions);
+        result.insert_str(position + inserted, &run);
+        inserted += run.len();
+    });
+
+    result += &Instruction::BegEnd.to_string();
+
+    result
+}
+
+impl ChallengeKind for Barcode {
+    fn version(&self) -> i32 {
+        GENERATOR_VERSION
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("Barcode parameters are always serializable.")
+    }
+
+    fn prompt(&self) -> &'static str {
+        if self.extended_instructions {
+            "Each case is a barcode of digits and instructions. \
+            '#' closes the current block and begins a new one, \
+            '!' appends the previous block to the current block, \
+            '^' reverses the most recently closed block, \
+            '%' encrypts the most recently closed block by doubling each digit modulo 10, \
+            '>' rotates the most recently closed block right by one digit, \
+            '~' swaps the two most recently closed blocks, \
+            '-' drops the most recently closed block, and \
+            '$' appends the sum of the digits of the most recently closed block modulo 10 \
+            to the current block. \
+            Instructions needing more closed blocks than there are do nothing. \
+            Decode each case to the concatenation of its blocks."
+        } else {
+            "Each case is a barcode of digits and instructions. \
+            '#' closes the current block and begins a new one, \
+            '!' appends the previous block to the current block, \
+            '^' reverses the most recently closed block, and \
+            '%' encrypts the most recently closed block by doubling each digit modulo 10. \
+            Decode each case to the concatenation of its blocks."
+        }
+    }
+
+    fn generate_cases(&self, mut rng: &mut dyn RngCore) -> Vec<String> {
+        let mut cases = self.mandatory_cases.clone();
+        let mut num_random_cases = self.num_random_cases;
+
+        // Witnesses are kept even when they outnumber the random cases.
+        if self.adversarial_cases {
+            let mut witnesses = Vec::new();
+            for bug in BarcodeBug::iter() {
+                for _ in 0..self.witnesses_per_bug {
+                    witnesses.push(bug.witness(&mut rng));
+                }
+            }
+            num_random_cases = num_random_cases.saturating_sub(witnesses.len());
+            cases.extend(witnesses);
+        }
+
+        cases.extend((0..num_random_cases).map(|_| self.generate_random_case(&mut rng)));
+
+        cases
+    }
+
+    fn solve(&self, case: &str) -> String {
+        parse_barcode(case)
+    }
+
+    fn diagnose(
+        &self,
+        cases: &[String],
+        submitted: &[String],
+        failed_cases: &[usize],
+    ) -> Option<String> {
+        BarcodeBug::diagnose(cases, submitted, failed_cases).map(|bug| bug.to_string())
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
 enum Instruction {
     #[default]
     BegEnd,
     Repeat,
     Reverse,
     Encrypt,
+    Rotate,
+    Swap,
+    Drop,
+    Checksum,
 }
 
 impl Display for Instruction {
@@ -51,107 +345,202 @@ impl Display for Instruction {
             Instruction::Repeat => write!(f, "!"),
             Instruction::Reverse => write!(f, "^"),
             Instruction::Encrypt => write!(f, "%"),
+            Instruction::Rotate => write!(f, ">"),
+            Instruction::Swap => write!(f, "~"),
+            Instruction::Drop => write!(f, "-"),
+            Instruction::Checksum => write!(f, "$"),
         }
     }
 }
 
 impl Instruction {
+    /// The instructions of every challenge issued before extended ones existed,
+    /// in the order they are drawn in.
+    const ORIGINAL: [Instruction; 4] = [
+        Instruction::BegEnd,
+        Instruction::Repeat,
+        Instruction::Reverse,
+        Instruction::Encrypt,
+    ];
+
+    const EXTENDED: [Instruction; 8] = [
+        Instruction::BegEnd,
+        Instruction::Repeat,
+        Instruction::Reverse,
+        Instruction::Encrypt,
+        Instruction::Rotate,
+        Instruction::Swap,
+        Instruction::Drop,
+        Instruction::Checksum,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Instruction::BegEnd => "beg_end",
+            Instruction::Repeat => "repeat",
+            Instruction::Reverse => "reverse",
+            Instruction::Encrypt => "encrypt",
+            Instruction::Rotate => "rotate",
+            Instruction::Swap => "swap",
+            Instruction::Drop => "drop",
+            Instruction::Checksum => "checksum",
+        }
+    }
+
     fn parse(value: &char) -> Option<Instruction> {
         match value {
             '#' => Some(Instruction::BegEnd),
             '!' => Some(Instruction::Repeat),
             '^' => Some(Instruction::Reverse),
             '%' => Some(Instruction::Encrypt),
+            '>' => Some(Instruction::Rotate),
+            '~' => Some(Instruction::Swap),
+            '-' => Some(Instruction::Drop),
+            '$' => Some(Instruction::Checksum),
             _ => None,
         }
     }
 }
 
-fn generate_random_case(rng: &mut impl Rng) -> String {
-    let num_numeric = rng.gen_range(32..=64);
-    let num_instructions = rng.gen_range(16..=32);
+/// What decoding a barcode has built so far.
+#[derive(Default)]
+struct Decoder {
+    result: Vec<String>,
+    current_block: String,
+    previous_block: String,
+}
 
-    let mut result = Instruction::BegEnd.to_string();
+impl Decoder {
+    fn step(&mut self, c: char) {
+        match Instruction::parse(&c) {
+            Some(instrunction) => match instrunction {
+                Instruction::BegEnd => {
+                    self.result.push(self.current_block.clone());
+                    self.previous_block = self.current_block.clone();
+                    self.current_block.clear();
+                }
+                Instruction::Repeat => {
+                    self.current_block += &self.previous_block;
+                }
+                Instruction::Reverse => {
+                    if let Some(last) = self.result.last_mut() {
+                        *last = last.chars().rev().collect();
+                    }
+                }
+                Instruction::Encrypt => {
+                    if let Some(last) = self.result.last_mut() {
+                        *last = last
+                            .chars()
+                            .map(|d| {
+                                if d == '0' {
+                                    '0'
+                                } else {
+                                    std::char::from_digit((d.to_digit(10).unwrap() * 2) % 10, 10)
+                                        .unwrap()
+                                }
+                            })
+                            .collect();
+                    }
+                }
+                Instruction::Rotate => {
+                    if let Some(last) = self.result.last_mut() {
+                        if let Some(digit) = last.pop() {
+                            last.insert(0, digit);
+                        }
+                    }
+                }
+                Instruction::Swap => {
+                    let len = self.result.len();
+                    if len >= 2 {
+                        self.result.swap(len - 1, len - 2);
+                    }
+                }
+                Instruction::Drop => {
+                    self.result.pop();
+                }
+                Instruction::Checksum => {
+                    if let Some(last) = self.result.last() {
+                        let checksum =
+                            last.chars().filter_map(|d| d.to_digit(10)).sum::<u32>() % 10;
+                        self.current_block += &checksum.to_string();
+                    }
+                }
+            },
+            None => {
+                self.current_block.push(c);
+            }
+        }
+    }
 
-    result += &(0..num_numeric)
-        .map(|_| rng.gen_range(0..=9).to_string())
-        .collect::<String>();
+    fn finish(mut self) -> String {
+        self.result.push(self.current_block);
+        self.result.concat()
+    }
+}
 
-    let mut instruction_positions: Vec<usize> = (1..num_numeric).collect();
-    instruction_positions.shuffle(rng);
-    instruction_positions.truncate(num_instructions);
-    instruction_positions.sort_unstable();
+pub fn parse_barcode(barcode: &str) -> String {
+    let mut decoder = Decoder::default();
 
-    instruction_positions
-        .iter()
-        .enumerate()
-        .for_each(|(i, &position)| {
-            if let Some(instruction) = Instruction::iter().choose(rng) {
-                let instruction_str = instruction.to_string();
-                let adjusted_position = position + i * instruction_str.len();
-                result.insert_str(adjusted_position, &instruction_str);
-            }
-        });
+    barcode.chars().for_each(|c| decoder.step(c));
 
-    result += &Instruction::BegEnd.to_string();
+    decoder.finish()
+}
 
-    result
+/// The decoder's state right after one symbol of a barcode.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+pub struct TraceStep {
+    /// Index of the symbol in the barcode.
+    pub position: usize,
+    pub symbol: String,
+    /// The instruction the symbol stands for; absent for digits.
+    pub instruction: Option<String>,
+    pub current_block: String,
+    pub previous_block: String,
+    /// Every closed block, as later instructions have left them.
+    pub result: Vec<String>,
 }
 
-pub fn parse_barcode(barcode: &str) -> String {
-    let mut result: Vec<String> = Vec::new();
-    let mut current_block = String::new();
-    let mut previous_block = String::new();
-
-    barcode.chars().for_each(|c| match Instruction::parse(&c) {
-        Some(instrunction) => match instrunction {
-            Instruction::BegEnd => {
-                result.push(current_block.clone());
-                previous_block = current_block.clone();
-                current_block.clear();
-            }
-            Instruction::Repeat => {
-                current_block += &previous_block;
-            }
-            Instruction::Reverse => {
-                if let Some(last) = result.last_mut() {
-                    *last = last.chars().rev().collect();
-                }
-            }
-            Instruction::Encrypt => {
-                if let Some(last) = result.last_mut() {
-                    *last = last
-                        .chars()
-                        .map(|d| {
-                            if d == '0' {
-                                '0'
-                            } else {
-                                std::char::from_digit((d.to_digit(10).unwrap() * 2) % 10, 10)
-                                    .unwrap()
-                            }
-                        })
-                        .collect();
-                }
-            }
-        },
-        None => {
-            current_block.push(c);
-        }
-    });
+/// Decodes `barcode` like `parse_barcode`, recording the effect of every symbol.
+pub fn trace_barcode(barcode: &str) -> Vec<TraceStep> {
+    let mut decoder = Decoder::default();
 
-    result.push(current_block);
-    result.concat()
+    barcode
+        .chars()
+        .enumerate()
+        .map(|(position, c)| {
+            decoder.step(c);
+
+            TraceStep {
+                position,
+                symbol: c.to_string(),
+                instruction: Instruction::parse(&c)
+                    .map(|instruction| instruction.name().to_string()),
+                current_block: decoder.current_block.clone(),
+                previous_block: decoder.previous_block.clone(),
+                result: decoder.result.clone(),
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::domain::algo_question::{
-        generate_challenge, generate_random_case, parse_barcode, Instruction,
+        parse_barcode, trace_barcode, Barcode, BarcodeBug, CountRange, Instruction, InstructionMix,
+        InstructionWeights,
     };
+    use crate::domain::ChallengeKind;
+    use claims::{assert_err, assert_ok};
+    use strum::IntoEnumIterator;
 
     #[test]
     fn test_generate_challenge() {
-        let challenge = generate_challenge(3, vec!["1234567890".to_string()]);
+        let barcode = Barcode {
+            num_random_cases: 3,
+            mandatory_cases: vec!["1234567890".to_string()],
+            ..Barcode::default()
+        };
+        let challenge = barcode.generate_challenge(rand::random());
         assert_eq!(challenge.challenge.len(), 4);
         assert_eq!(challenge.solution.len(), 4);
 
@@ -160,7 +549,7 @@ mod tests {
 
     #[test]
     fn test_generated_random_case() {
-        let generated_case = generate_random_case(&mut rand::thread_rng());
+        let generated_case = Barcode::default().generate_random_case(&mut rand::thread_rng());
 
         assert_eq!(
             generated_case.chars().next().unwrap(),
@@ -172,6 +561,186 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generated_random_case_respects_configured_ranges() {
+        let barcode = Barcode {
+            num_digits: CountRange { min: 8, max: 10 },
+            num_instructions: CountRange { min: 2, max: 4 },
+            ..Barcode::default()
+        };
+
+        for _ in 0..64 {
+            let generated_case = barcode.generate_random_case(&mut rand::thread_rng());
+            let num_digits = generated_case
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .count();
+            let num_instructions = generated_case.chars().count() - num_digits - 2;
+
+            assert!((8..=10).contains(&num_digits));
+            assert!((2..=4).contains(&num_instructions));
+        }
+    }
+
+    #[test]
+    fn test_instruction_weights_pick_the_mix() {
+        let barcode = Barcode {
+            instruction_mix: InstructionMix {
+                weights: Some(InstructionWeights {
+                    beg_end: 0,
+                    repeat: 1,
+                    reverse: 0,
+                    encrypt: 0,
+                    rotate: 0,
+                    swap: 0,
+                    drop: 0,
+                    checksum: 0,
+                }),
+                max_run: 3,
+            },
+            ..Barcode::default()
+        };
+
+        let mut longest_run = 0;
+        for _ in 0..64 {
+            let generated_case = barcode.generate_random_case(&mut rand::thread_rng());
+            let inner = &generated_case[1..generated_case.len() - 1];
+
+            assert!(inner.chars().all(|c| c.is_ascii_digit() || c == '!'));
+            longest_run = inner
+                .split(|c: char| c.is_ascii_digit())
+                .map(str::len)
+                .fold(longest_run, usize::max);
+        }
+
+        assert!((2..=3).contains(&longest_run));
+    }
+
+    #[test]
+    fn test_invalid_instruction_mixes_are_rejected() {
+        assert_err!(Barcode {
+            instruction_mix: InstructionMix {
+                weights: None,
+                max_run: 0,
+            },
+            ..Barcode::default()
+        }
+        .validate());
+
+        assert_err!(Barcode {
+            instruction_mix: InstructionMix {
+                weights: Some(InstructionWeights {
+                    beg_end: 0,
+                    repeat: 0,
+                    reverse: 0,
+                    encrypt: 0,
+                    rotate: 0,
+                    swap: 0,
+                    drop: 0,
+                    checksum: 0,
+                }),
+                max_run: 1,
+            },
+            ..Barcode::default()
+        }
+        .validate());
+    }
+
+    #[test]
+    fn test_adversarial_challenges_catch_every_bug() {
+        let barcode = Barcode {
+            num_random_cases: 16,
+            mandatory_cases: Vec::new(),
+            adversarial_cases: true,
+            ..Barcode::default()
+        };
+
+        for seed in 0..32 {
+            let challenge = barcode.generate_challenge(seed);
+
+            assert_eq!(challenge.challenge.len(), 16);
+            for bug in BarcodeBug::iter() {
+                assert!(
+                    challenge
+                        .challenge
+                        .iter()
+                        .any(|case| bug.is_caught_by(case)),
+                    "No case catches {} for seed {}.",
+                    bug,
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_adversarial_cases_are_kept_without_random_cases() {
+        let barcode = Barcode {
+            num_random_cases: 0,
+            adversarial_cases: true,
+            ..Barcode::default()
+        };
+
+        let challenge = barcode.generate_challenge(rand::random());
+
+        assert_eq!(
+            challenge.challenge.len(),
+            barcode.mandatory_cases.len() + BarcodeBug::iter().count()
+        );
+    }
+
+    #[test]
+    fn test_witnesses_per_bug_adds_cases() {
+        let barcode = Barcode {
+            num_random_cases: 0,
+            adversarial_cases: true,
+            witnesses_per_bug: 3,
+            ..Barcode::default()
+        };
+
+        let challenge = barcode.generate_challenge(rand::random());
+
+        assert_eq!(
+            challenge.challenge.len(),
+            barcode.mandatory_cases.len() + 3 * BarcodeBug::iter().count()
+        );
+    }
+
+    #[test]
+    fn test_default_parameters_are_valid() {
+        assert_ok!(Barcode::default().validate());
+    }
+
+    #[test]
+    fn test_inverted_ranges_are_rejected() {
+        assert_err!(Barcode {
+            num_digits: CountRange { min: 64, max: 32 },
+            ..Barcode::default()
+        }
+        .validate());
+        assert_err!(Barcode {
+            num_instructions: CountRange { min: 8, max: 4 },
+            ..Barcode::default()
+        }
+        .validate());
+    }
+
+    #[test]
+    fn test_mandatory_cases_with_unknown_characters_are_rejected() {
+        assert_err!(Barcode {
+            mandatory_cases: vec!["#12a#".to_string()],
+            ..Barcode::default()
+        }
+        .validate());
+    }
+
+    #[test]
+    fn test_missing_parameters_fall_back_to_defaults() {
+        let barcode: Barcode = serde_json::from_value(serde_json::json!({})).unwrap();
+
+        assert_eq!(barcode.parameters(), Barcode::default().parameters());
+    }
+
     #[test]
     fn test_parse_barcode_example() {
         assert_eq!(parse_barcode("#12#34!#59^#67%#"), "1221430867");
@@ -181,4 +750,75 @@ mod tests {
     fn test_parse_barcode_edge() {
         assert_eq!(parse_barcode("#12^!%%###34^#"), "1234");
     }
+
+    #[test]
+    fn test_trace_barcode_records_every_symbol() {
+        let trace = trace_barcode("#12#3!^#");
+
+        assert_eq!(trace.len(), 8);
+        assert_eq!(trace[0].instruction.as_deref(), Some("beg_end"));
+        assert_eq!(trace[1].instruction, None);
+
+        // '!' appends the block as it was closed.
+        assert_eq!(trace[5].current_block, "312");
+        assert_eq!(trace[5].previous_block, "12");
+
+        // '^' reverses the last closed block, not the current one.
+        assert_eq!(trace[6].result, vec!["", "21"]);
+        assert_eq!(trace[6].current_block, "312");
+    }
+
+    #[test]
+    fn test_trace_barcode_ends_with_the_decoded_barcode() {
+        let barcode = "#12#34!#59^#67%#";
+        let last = trace_barcode(barcode).pop().unwrap();
+
+        assert!(last.current_block.is_empty());
+        assert_eq!(last.result.concat(), parse_barcode(barcode));
+    }
+
+    #[test]
+    fn test_parse_barcode_extended_instructions() {
+        assert_eq!(parse_barcode("#123#>#"), "312");
+        assert_eq!(parse_barcode("#12#34#~#"), "3412");
+        assert_eq!(parse_barcode("#12#34#-#"), "12");
+        assert_eq!(parse_barcode("#19#$#"), "190");
+    }
+
+    #[test]
+    fn test_extended_instructions_without_enough_blocks_do_nothing() {
+        assert_eq!(parse_barcode("12>~-$#"), "12");
+    }
+
+    #[test]
+    fn test_extended_instructions_are_only_generated_when_enabled() {
+        let extended = |case: &String| case.chars().any(|c| ">~-$".contains(c));
+
+        let original = Barcode::default().generate_challenge(rand::random());
+        assert!(!original.challenge.iter().any(extended));
+
+        let barcode = Barcode {
+            extended_instructions: true,
+            ..Barcode::default()
+        };
+        let challenge = barcode.generate_challenge(rand::random());
+        assert!(challenge.challenge.iter().any(extended));
+        assert!(barcode.prompt().contains("'~'"));
+        assert!(!Barcode::default().prompt().contains("'~'"));
+    }
+
+    #[test]
+    fn test_extended_mandatory_cases_need_extended_instructions() {
+        let barcode = Barcode {
+            mandatory_cases: vec!["#12#34~#".to_string()],
+            ..Barcode::default()
+        };
+        assert_err!(barcode.validate());
+
+        assert_ok!(Barcode {
+            extended_instructions: true,
+            ..barcode
+        }
+        .validate());
+    }
 }
@@ -0,0 +1,290 @@
+//! Known-incorrect ways of decoding barcodes. Every challenge issued with
+//! adversarial cases contains a case each of these gets wrong, so a solution
+//! with one of these bugs cannot pass.
+
+use rand::Rng;
+use strum::IntoEnumIterator;
+
+use crate::domain::algo_question::{
+    parse_barcode, random_case, CountRange, Decoder, Instruction, InstructionMix,
+};
+
+/// Random attempts at a case catching a bug before falling back to its example.
+const WITNESS_ATTEMPTS: usize = 256;
+
+#[derive(strum::EnumIter, strum::Display, Clone, Copy, Debug, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum BarcodeBug {
+    /// '!' appends the current block to itself instead of the previous block.
+    RepeatCurrentBlock,
+    /// '!' repeats the current block when the previous block is empty, as
+    /// before any digits have been closed in a block.
+    RepeatBeforeAnyBlock,
+    /// '!' appends the previous block as modified by later '^' and '%',
+    /// rather than as it was closed.
+    RepeatModifiedBlock,
+    /// '^' reverses the current block instead of the last closed one.
+    ReverseCurrentBlock,
+    /// '^' reverses the current block when the last closed block is empty.
+    ReverseOnEmptyResult,
+    /// '%' encrypts the current block instead of the last closed one.
+    EncryptCurrentBlock,
+    /// '%' doubles each digit without wrapping, so 5 becomes 10.
+    EncryptWithoutModulo,
+}
+
+impl BarcodeBug {
+    /// Decodes `barcode` the way a solution with this bug would. Only the
+    /// buggy instruction is decoded here; the rest is left to the reference.
+    pub fn parse(&self, barcode: &str) -> String {
+        let mut decoder = Decoder::default();
+
+        for c in barcode.chars() {
+            match (self, Instruction::parse(&c)) {
+                (BarcodeBug::RepeatCurrentBlock, Some(Instruction::Repeat)) => {
+                    decoder.current_block = decoder.current_block.repeat(2)
+                }
+                (BarcodeBug::RepeatBeforeAnyBlock, Some(Instruction::Repeat))
+                    if decoder.previous_block.is_empty() =>
+                {
+                    decoder.current_block = decoder.current_block.repeat(2)
+                }
+                (BarcodeBug::RepeatModifiedBlock, Some(Instruction::Repeat)) => {
+                    let last = decoder.result.last().cloned().unwrap_or_default();
+                    decoder.current_block += &last
+                }
+                (BarcodeBug::ReverseCurrentBlock, Some(Instruction::Reverse)) => {
+                    decoder.current_block = decoder.current_block.chars().rev().collect()
+                }
+                #[allow(clippy::unnecessary_map_or)] // Option::is_none_or needs Rust 1.82
+                (BarcodeBug::ReverseOnEmptyResult, Some(Instruction::Reverse))
+                    if decoder.result.last().map_or(true, String::is_empty) =>
+                {
+                    decoder.current_block = decoder.current_block.chars().rev().collect()
+                }
+                (BarcodeBug::EncryptCurrentBlock, Some(Instruction::Encrypt)) => {
+                    decoder.current_block = encrypt(&decoder.current_block, true)
+                }
+                (BarcodeBug::EncryptWithoutModulo, Some(Instruction::Encrypt)) => {
+                    if let Some(last) = decoder.result.last_mut() {
+                        *last = encrypt(last, false)
+                    }
+                }
+                _ => decoder.step(c),
+            }
+        }
+
+        decoder.finish()
+    }
+
+    /// Whether a solution with this bug gets `case` wrong.
+    pub fn is_caught_by(&self, case: &str) -> bool {
+        self.parse(case) != parse_barcode(case)
+    }
+
+    /// A fixed case this bug gets wrong.
+    pub fn example(&self) -> &'static str {
+        match self {
+            BarcodeBug::RepeatCurrentBlock | BarcodeBug::RepeatBeforeAnyBlock => "#12!#",
+            BarcodeBug::RepeatModifiedBlock => "#12#3^4!5#",
+            BarcodeBug::ReverseCurrentBlock | BarcodeBug::ReverseOnEmptyResult => "#12^#",
+            BarcodeBug::EncryptCurrentBlock => "#12%#",
+            BarcodeBug::EncryptWithoutModulo => "#5#%#",
+        }
+    }
+
+    /// The bug explaining the most failed cases of a submission, if any
+    /// failed answer is what a buggy decoder would have given. Ties go to the
+    /// bug listed first.
+    pub fn diagnose(
+        cases: &[String],
+        submitted: &[String],
+        failed_cases: &[usize],
+    ) -> Option<BarcodeBug> {
+        BarcodeBug::iter()
+            .map(|bug| {
+                let explained = failed_cases
+                    .iter()
+                    .filter(|&&i| match (cases.get(i), submitted.get(i)) {
+                        (Some(case), Some(answer)) => bug.parse(case) == *answer,
+                        _ => false,
+                    })
+                    .count();
+                (bug, explained)
+            })
+            .filter(|(_, explained)| *explained > 0)
+            .fold(
+                None,
+                |best: Option<(BarcodeBug, usize)>, (bug, explained)| match best {
+                    Some((_, most)) if most >= explained => best,
+                    _ => Some((bug, explained)),
+                },
+            )
+            .map(|(bug, _)| bug)
+    }
+
+    /// A short random case this bug gets wrong, so the catching cases differ
+    /// between challenges.
+    pub fn witness(&self, rng: &mut impl Rng) -> String {
+        let num_digits = CountRange { min: 2, max: 10 };
+        let num_instructions = CountRange { min: 1, max: 9 };
+
+        (0..WITNESS_ATTEMPTS)
+            .map(|_| {
+                random_case(
+                    rng,
+                    num_digits,
+                    num_instructions,
+                    &InstructionMix::default(),
+                    &Instruction::ORIGINAL,
+                )
+            })
+            .find(|case| self.is_caught_by(case))
+            .unwrap_or_else(|| self.example().to_string())
+    }
+}
+
+/// Doubles each digit of `block`, modulo 10 when `wrap` is set.
+fn encrypt(block: &str, wrap: bool) -> String {
+    block
+        .chars()
+        .filter_map(|d| d.to_digit(10))
+        .map(|d| if wrap { (d * 2) % 10 } else { d * 2 })
+        .map(|d| d.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::algo_question::bug_zoo::BarcodeBug;
+    use crate::domain::algo_question::parse_barcode;
+    use claims::{assert_none, assert_some_eq};
+    use strum::IntoEnumIterator;
+
+    fn cases() -> Vec<String> {
+        ["#12%#", "#5#%#", "#1234#5678#"]
+            .iter()
+            .map(|case| case.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn every_example_catches_its_bug() {
+        for bug in BarcodeBug::iter() {
+            assert!(
+                bug.is_caught_by(bug.example()),
+                "{} is not caught by its example.",
+                bug
+            );
+        }
+    }
+
+    /// A case, its correct answer, and the answers of the bugs it catches.
+    type HandDecoded = (
+        &'static str,
+        &'static str,
+        &'static [(BarcodeBug, &'static str)],
+    );
+
+    /// Answers worked out by hand rather than by `Decoder`, which the bugs
+    /// share. Bugs a case does not catch must give the correct answer.
+    const HAND_DECODED: [HandDecoded; 7] = [
+        (
+            "#12!#",
+            "12",
+            &[
+                (BarcodeBug::RepeatCurrentBlock, "1212"),
+                (BarcodeBug::RepeatBeforeAnyBlock, "1212"),
+            ],
+        ),
+        (
+            "#12#34!#",
+            "123412",
+            &[(BarcodeBug::RepeatCurrentBlock, "123434")],
+        ),
+        (
+            "#12#3^4!5#",
+            "2134125",
+            &[
+                (BarcodeBug::RepeatCurrentBlock, "2134345"),
+                (BarcodeBug::RepeatModifiedBlock, "2134215"),
+                (BarcodeBug::ReverseCurrentBlock, "1234125"),
+            ],
+        ),
+        (
+            "#12^#",
+            "12",
+            &[
+                (BarcodeBug::ReverseCurrentBlock, "21"),
+                (BarcodeBug::ReverseOnEmptyResult, "21"),
+            ],
+        ),
+        ("#12%#", "12", &[(BarcodeBug::EncryptCurrentBlock, "24")]),
+        (
+            "#5#%#",
+            "0",
+            &[
+                (BarcodeBug::EncryptCurrentBlock, "5"),
+                (BarcodeBug::EncryptWithoutModulo, "10"),
+            ],
+        ),
+        ("#1234#5678#", "12345678", &[]),
+    ];
+
+    #[test]
+    fn bugs_decode_to_hand_computed_answers() {
+        for (case, correct, wrong) in HAND_DECODED {
+            assert_eq!(parse_barcode(case), correct, "Reference decoding {}", case);
+
+            for bug in BarcodeBug::iter() {
+                let expected = wrong
+                    .iter()
+                    .find(|(wrong_bug, _)| *wrong_bug == bug)
+                    .map_or(correct, |(_, answer)| answer);
+
+                assert_eq!(bug.parse(case), expected, "{} decoding {}", bug, case);
+            }
+        }
+    }
+
+    #[test]
+    fn diagnose_names_the_bug_behind_the_failed_cases() {
+        let cases = cases();
+        let submitted = cases
+            .iter()
+            .map(|case| BarcodeBug::EncryptWithoutModulo.parse(case))
+            .collect::<Vec<String>>();
+
+        // Only "#5#%#" is decoded differently without the modulo.
+        assert_some_eq!(
+            BarcodeBug::diagnose(&cases, &submitted, &[1]),
+            BarcodeBug::EncryptWithoutModulo
+        );
+    }
+
+    #[test]
+    fn diagnose_finds_nothing_for_unexplained_answers() {
+        let submitted = vec!["wrong".to_string(); 3];
+
+        assert_none!(BarcodeBug::diagnose(&cases(), &submitted, &[0, 1, 2]));
+    }
+
+    #[test]
+    fn diagnose_ignores_missing_answers() {
+        assert_none!(BarcodeBug::diagnose(&cases(), &[], &[0, 1, 2]));
+    }
+
+    #[test]
+    fn witnesses_catch_their_bug() {
+        let mut rng = rand::thread_rng();
+
+        for bug in BarcodeBug::iter() {
+            for _ in 0..16 {
+                let witness = bug.witness(&mut rng);
+
+                assert!(bug.is_caught_by(&witness));
+                assert!(witness.starts_with('#') && witness.ends_with('#'));
+            }
+        }
+    }
+}
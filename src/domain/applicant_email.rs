@@ -0,0 +1,151 @@
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ApplicantEmail(String);
+
+impl ApplicantEmail {
+    pub fn parse(s: &str) -> Result<ApplicantEmail, String> {
+        let invalid = || format!("Invalid email! Given: {}", s);
+
+        let trimmed = s.trim();
+
+        let (local_part, domain) = trimmed.rsplit_once('@').ok_or_else(invalid)?;
+
+        let is_too_long = trimmed.len() > 254 || local_part.len() > 64;
+
+        let local_part_is_valid = !local_part.is_empty()
+            && !local_part.starts_with('.')
+            && !local_part.ends_with('.')
+            && !local_part.contains("..")
+            && local_part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c));
+
+        let labels = domain.split('.').collect::<Vec<_>>();
+        let domain_is_valid = labels.len() >= 2
+            && labels.iter().all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+
+        if is_too_long || !local_part_is_valid || !domain_is_valid {
+            Err(invalid())
+        } else {
+            // Domains are case insensitive, so they are stored lowercased.
+            Ok(Self(format!(
+                "{}@{}",
+                local_part,
+                domain.to_ascii_lowercase()
+            )))
+        }
+    }
+
+    pub fn domain(&self) -> &str {
+        self.0.rsplit_once('@').map_or("", |(_, domain)| domain)
+    }
+
+    /// Whether the email belongs to one of `domains` or one of their subdomains.
+    /// An empty list allows every domain.
+    pub fn is_in_domains(&self, domains: &[String]) -> bool {
+        let domain = self.domain();
+
+        domains.is_empty()
+            || domains.iter().any(|allowed| {
+                let allowed = allowed.trim().trim_start_matches('@').to_ascii_lowercase();
+                domain == allowed || domain.ends_with(&format!(".{}", allowed))
+            })
+    }
+}
+
+impl AsRef<str> for ApplicantEmail {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::ApplicantEmail;
+    use claims::{assert_err, assert_ok};
+
+    #[test]
+    fn a_valid_email_is_accepted() {
+        assert_ok!(ApplicantEmail::parse("ladley.g@northeastern.edu"));
+    }
+
+    #[test]
+    fn the_domain_is_lowercased() {
+        let email = ApplicantEmail::parse("Garrett@Northeastern.EDU").unwrap();
+        assert_eq!(email.as_ref(), "Garrett@northeastern.edu");
+    }
+
+    #[test]
+    fn empty_string_is_rejected() {
+        assert_err!(ApplicantEmail::parse(""));
+    }
+
+    #[test]
+    fn email_missing_at_symbol_is_rejected() {
+        assert_err!(ApplicantEmail::parse("garrettnortheastern.edu"));
+    }
+
+    #[test]
+    fn email_missing_local_part_is_rejected() {
+        assert_err!(ApplicantEmail::parse("@northeastern.edu"));
+    }
+
+    #[test]
+    fn email_with_a_dotless_domain_is_rejected() {
+        assert_err!(ApplicantEmail::parse("garrett@localhost"));
+    }
+
+    #[test]
+    fn email_with_whitespace_is_rejected() {
+        assert_err!(ApplicantEmail::parse("garrett ladley@northeastern.edu"));
+    }
+
+    #[test]
+    fn email_with_consecutive_dots_is_rejected() {
+        assert_err!(ApplicantEmail::parse("garrett..ladley@northeastern.edu"));
+        assert_err!(ApplicantEmail::parse("garrett@northeastern..edu"));
+    }
+
+    #[test]
+    fn email_with_a_too_long_local_part_is_rejected() {
+        assert_err!(ApplicantEmail::parse(&format!(
+            "{}@northeastern.edu",
+            "a".repeat(65)
+        )));
+    }
+
+    #[test]
+    fn an_empty_allowlist_allows_every_domain() {
+        let email = ApplicantEmail::parse("garrett@gmail.com").unwrap();
+        assert!(email.is_in_domains(&[]));
+    }
+
+    #[test]
+    fn allowlisted_domains_and_their_subdomains_are_allowed() {
+        let allowed = vec!["northeastern.edu".to_string()];
+
+        assert!(ApplicantEmail::parse("garrett@northeastern.edu")
+            .unwrap()
+            .is_in_domains(&allowed));
+        assert!(ApplicantEmail::parse("garrett@ccs.northeastern.edu")
+            .unwrap()
+            .is_in_domains(&allowed));
+    }
+
+    #[test]
+    fn other_domains_are_not_allowed() {
+        let allowed = vec!["northeastern.edu".to_string()];
+
+        assert!(!ApplicantEmail::parse("garrett@gmail.com")
+            .unwrap()
+            .is_in_domains(&allowed));
+        assert!(!ApplicantEmail::parse("garrett@notnortheastern.edu")
+            .unwrap()
+            .is_in_domains(&allowed));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::domain::Nuid;
+
+/// The orders reviewers can list applicants in. Ties are broken by NUID.
+#[derive(
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    strum::Display,
+    strum::EnumString,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ApplicantSort {
+    #[default]
+    RegistrationTime,
+    /// Fastest first; applicants who have not submitted come last. No index
+    /// covers this order, so each page sorts every matching applicant.
+    TimeToCompletion,
+}
+
+/// Where a page of the applicant listing ended. The next page starts right
+/// after it, so applicants registering in between do not shift the pages.
+#[derive(Debug, PartialEq)]
+pub struct ApplicantCursor {
+    pub sort: ApplicantSort,
+    /// The sort key of the last applicant on the page, in microseconds.
+    pub sort_key: i64,
+    pub nuid: String,
+}
+
+impl std::fmt::Display for ApplicantCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.sort, self.sort_key, self.nuid)
+    }
+}
+
+impl ApplicantCursor {
+    pub fn parse(s: &str) -> Result<ApplicantCursor, String> {
+        let invalid = || format!("Invalid cursor! Given: {}", s);
+
+        let mut parts = s.splitn(3, ':');
+        let sort = parts
+            .next()
+            .and_then(|sort| sort.parse().ok())
+            .ok_or_else(invalid)?;
+        let sort_key = parts
+            .next()
+            .and_then(|sort_key| sort_key.parse().ok())
+            .ok_or_else(invalid)?;
+        let nuid = parts
+            .next()
+            .and_then(|nuid| Nuid::parse(nuid).ok())
+            .ok_or_else(invalid)?;
+
+        Ok(Self {
+            sort,
+            sort_key,
+            nuid: nuid.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{ApplicantCursor, ApplicantSort};
+    use claims::{assert_err, assert_ok_eq};
+
+    #[test]
+    fn a_cursor_parses_back_from_its_display() {
+        let cursor = ApplicantCursor {
+            sort: ApplicantSort::TimeToCompletion,
+            sort_key: 1_694_000_000_000_000,
+            nuid: "001234567".to_string(),
+        };
+
+        assert_ok_eq!(ApplicantCursor::parse(&cursor.to_string()), cursor);
+    }
+
+    #[test]
+    fn a_cursor_with_an_unknown_sort_is_rejected() {
+        assert_err!(ApplicantCursor::parse("name:0:001234567"));
+    }
+
+    #[test]
+    fn a_cursor_with_a_non_numeric_key_is_rejected() {
+        assert_err!(ApplicantCursor::parse("registration_time:abc:001234567"));
+    }
+
+    #[test]
+    fn a_cursor_with_an_invalid_nuid_is_rejected() {
+        assert_err!(ApplicantCursor::parse("registration_time:0:abc"));
+    }
+
+    #[test]
+    fn a_truncated_cursor_is_rejected() {
+        assert_err!(ApplicantCursor::parse("registration_time:0"));
+    }
+}
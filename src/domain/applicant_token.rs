@@ -0,0 +1,90 @@
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+use uuid::Uuid;
+
+/// The credential an applicant uses to fetch and submit their challenge.
+/// Only its keyed hash is stored, so a database dump does not leak it.
+#[derive(Debug, PartialEq)]
+pub struct ApplicantToken(Uuid);
+
+impl std::fmt::Display for ApplicantToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl ApplicantToken {
+    pub fn generate() -> ApplicantToken {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn parse(s: &str) -> Result<ApplicantToken, String> {
+        Uuid::parse_str(s)
+            .map(Self)
+            .map_err(|_| format!("Invalid token! Given: {}", s))
+    }
+
+    /// HMAC-SHA256 of the canonical form, so every spelling of the same UUID
+    /// hashes alike.
+    pub fn hash(&self, secret: &Secret<String>) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC can take a key of any size");
+        mac.update(self.0.hyphenated().to_string().as_bytes());
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+}
+
+impl From<Uuid> for ApplicantToken {
+    fn from(token: Uuid) -> Self {
+        Self(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::ApplicantToken;
+    use claims::{assert_err, assert_ok};
+    use secrecy::Secret;
+
+    #[test]
+    fn a_uuid_is_a_valid_token() {
+        assert_ok!(ApplicantToken::parse(
+            "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        ));
+    }
+
+    #[test]
+    fn a_non_uuid_is_rejected() {
+        assert_err!(ApplicantToken::parse("not-a-token"));
+    }
+
+    #[test]
+    fn hashes_do_not_depend_on_how_the_token_is_spelled() {
+        let secret = Secret::new("secret".to_string());
+
+        let lower = ApplicantToken::parse("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let upper = ApplicantToken::parse("67E55044-10B1-426F-9247-BB680E5FE0C8").unwrap();
+
+        assert_eq!(lower.hash(&secret), upper.hash(&secret));
+    }
+
+    #[test]
+    fn hashes_depend_on_the_secret() {
+        let token = ApplicantToken::generate();
+
+        assert_ne!(
+            token.hash(&Secret::new("first".to_string())),
+            token.hash(&Secret::new("second".to_string()))
+        );
+    }
+
+    #[test]
+    fn hashes_do_not_contain_the_token() {
+        let token = ApplicantToken::generate();
+        let hash = token.hash(&Secret::new("secret".to_string()));
+
+        assert!(!hash.contains(&token.to_string()));
+        assert_eq!(hash.len(), 64);
+    }
+}
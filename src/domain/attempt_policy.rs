@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use crate::errors::retry_after;
+
+/// Limits on how often an applicant may submit, to stop them from bisecting
+/// which cases are wrong.
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct AttemptPolicy {
+    /// `None` allows unlimited attempts.
+    pub max_attempts: Option<u32>,
+    pub min_interval_seconds: u64,
+}
+
+pub struct PreviousAttempts {
+    pub count: u32,
+    pub last_submission_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AttemptRejection {
+    Exhausted,
+    TooSoon { retry_after: Duration },
+}
+
+impl std::fmt::Display for AttemptRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttemptRejection::Exhausted => write!(f, "No submission attempts remaining!"),
+            AttemptRejection::TooSoon { retry_after } => write!(
+                f,
+                "Submitted too soon after the previous attempt! Retry after {} seconds.",
+                retry_after.as_secs()
+            ),
+        }
+    }
+}
+
+impl AttemptPolicy {
+    /// Checks whether another attempt is allowed at `now`, returning how many
+    /// attempts will remain once it has been recorded.
+    pub fn check(
+        &self,
+        previous: &PreviousAttempts,
+        now: DateTime<Utc>,
+    ) -> Result<Option<u32>, AttemptRejection> {
+        if let Some(max_attempts) = self.max_attempts {
+            if previous.count >= max_attempts {
+                return Err(AttemptRejection::Exhausted);
+            }
+        }
+
+        if let Some(last_submission_time) = previous.last_submission_time {
+            let min_interval = chrono::Duration::seconds(self.min_interval_seconds as i64);
+            let next_allowed = last_submission_time + min_interval;
+
+            if now < next_allowed {
+                let remaining = (next_allowed - now).to_std().unwrap_or_default();
+                return Err(AttemptRejection::TooSoon {
+                    retry_after: retry_after(remaining),
+                });
+            }
+        }
+
+        Ok(self
+            .max_attempts
+            .map(|max_attempts| max_attempts - previous.count - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{AttemptPolicy, AttemptRejection, PreviousAttempts};
+    use chrono::{Duration, Utc};
+    use claims::{assert_err_eq, assert_ok_eq};
+
+    fn policy(max_attempts: Option<u32>, min_interval_seconds: u64) -> AttemptPolicy {
+        AttemptPolicy {
+            max_attempts,
+            min_interval_seconds,
+        }
+    }
+
+    #[test]
+    fn a_first_attempt_is_allowed() {
+        let previous = PreviousAttempts {
+            count: 0,
+            last_submission_time: None,
+        };
+
+        assert_ok_eq!(policy(Some(3), 60).check(&previous, Utc::now()), Some(2));
+    }
+
+    #[test]
+    fn unlimited_attempts_have_no_remaining_count() {
+        let previous = PreviousAttempts {
+            count: 100,
+            last_submission_time: None,
+        };
+
+        assert_ok_eq!(policy(None, 0).check(&previous, Utc::now()), None);
+    }
+
+    #[test]
+    fn attempts_beyond_the_maximum_are_rejected() {
+        let now = Utc::now();
+        let previous = PreviousAttempts {
+            count: 3,
+            last_submission_time: Some(now - Duration::days(1)),
+        };
+
+        assert_err_eq!(
+            policy(Some(3), 60).check(&previous, now),
+            AttemptRejection::Exhausted
+        );
+    }
+
+    #[test]
+    fn attempts_within_the_interval_are_rejected_with_a_rounded_up_retry_after() {
+        let now = Utc::now();
+        let previous = PreviousAttempts {
+            count: 1,
+            last_submission_time: Some(now - Duration::milliseconds(20_500)),
+        };
+
+        assert_err_eq!(
+            policy(Some(3), 60).check(&previous, now),
+            AttemptRejection::TooSoon {
+                retry_after: std::time::Duration::from_secs(40)
+            }
+        );
+    }
+
+    #[test]
+    fn attempts_after_the_interval_are_allowed() {
+        let now = Utc::now();
+        let previous = PreviousAttempts {
+            count: 1,
+            last_submission_time: Some(now - Duration::seconds(60)),
+        };
+
+        assert_ok_eq!(policy(Some(3), 60).check(&previous, now), Some(1));
+    }
+}
@@ -0,0 +1,169 @@
+use rand::{seq::SliceRandom, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::domain::algo_question::Barcode;
+
+#[derive(Debug)]
+pub struct Challenge {
+    pub challenge: Vec<String>,
+    pub solution: Vec<String>,
+}
+
+/// A family of puzzles that can be issued to applicants.
+pub trait ChallengeKind: std::fmt::Debug + Send + Sync {
+    /// Bumped whenever a change would make an existing seed produce different cases.
+    fn version(&self) -> i32;
+
+    /// The parameters this kind was built from, persisted so the challenge can
+    /// be regenerated after configuration changes.
+    fn parameters(&self) -> serde_json::Value;
+
+    /// Human readable description of the puzzle returned alongside the cases.
+    fn prompt(&self) -> &'static str;
+
+    /// Every case of a fresh challenge, in no particular order.
+    fn generate_cases(&self, rng: &mut dyn RngCore) -> Vec<String>;
+
+    /// The expected answer for a single case.
+    fn solve(&self, case: &str) -> String;
+
+    /// The misconception that best explains the failed cases of a submission,
+    /// for reviewers only.
+    fn diagnose(
+        &self,
+        cases: &[String],
+        submitted: &[String],
+        failed_cases: &[usize],
+    ) -> Option<String>;
+
+    /// Deterministically generates a challenge, so the same seed and version
+    /// always yield the same cases in the same order.
+    fn generate_challenge(&self, seed: u64) -> Challenge {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let mut challenge = self.generate_cases(&mut rng);
+
+        challenge.shuffle(&mut rng);
+
+        let solution = challenge
+            .iter()
+            .map(|case| self.solve(case))
+            .collect::<Vec<String>>();
+
+        Challenge {
+            challenge,
+            solution,
+        }
+    }
+}
+
+/// The name a challenge kind is configured and persisted under.
+#[derive(
+    serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, strum::Display, strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ChallengeKindName {
+    #[default]
+    Barcode,
+}
+
+impl ChallengeKindName {
+    pub fn parse(s: &str) -> Result<ChallengeKindName, String> {
+        s.parse()
+            .map_err(|_| format!("Invalid challenge kind! Given: {}", s))
+    }
+
+    /// Builds this kind from its parameters, rejecting invalid ones.
+    pub fn challenge_kind(
+        &self,
+        parameters: &serde_json::Value,
+    ) -> Result<Box<dyn ChallengeKind>, String> {
+        match self {
+            ChallengeKindName::Barcode => {
+                let barcode: Barcode = serde_json::from_value(parameters.clone())
+                    .map_err(|e| format!("Invalid barcode parameters! {}", e))?;
+                barcode.validate()?;
+                Ok(Box::new(barcode))
+            }
+        }
+    }
+
+    /// Rebuilds the exact challenge and solution issued from a stored seed.
+    pub fn regenerate_challenge(
+        &self,
+        seed: u64,
+        version: i32,
+        parameters: &serde_json::Value,
+    ) -> Result<Challenge, String> {
+        let challenge_kind = self.challenge_kind(parameters)?;
+
+        if challenge_kind.version() != version {
+            return Err(format!(
+                "Unsupported {} generator version! Given: {}, current: {}",
+                self,
+                version,
+                challenge_kind.version()
+            ));
+        }
+
+        Ok(challenge_kind.generate_challenge(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::ChallengeKindName;
+    use claims::{assert_err, assert_ok, assert_ok_eq};
+    use serde_json::json;
+
+    #[test]
+    fn challenge_kind_names_round_trip() {
+        let name = ChallengeKindName::Barcode;
+        assert_ok_eq!(ChallengeKindName::parse(&name.to_string()), name);
+    }
+
+    #[test]
+    fn unknown_challenge_kinds_are_rejected() {
+        assert_err!(ChallengeKindName::parse("sudoku"));
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        assert_err!(
+            ChallengeKindName::Barcode.challenge_kind(&json!({ "num_random_cases": "many" }))
+        );
+    }
+
+    #[test]
+    fn the_same_seed_regenerates_the_same_challenge() {
+        let name = ChallengeKindName::Barcode;
+        let parameters = json!({});
+        let version = assert_ok!(name.challenge_kind(&parameters)).version();
+
+        let first = assert_ok!(name.regenerate_challenge(42, version, &parameters));
+        let second = assert_ok!(name.regenerate_challenge(42, version, &parameters));
+
+        assert_eq!(first.challenge, second.challenge);
+        assert_eq!(first.solution, second.solution);
+    }
+
+    #[test]
+    fn different_seeds_generate_different_challenges() {
+        let challenge_kind = assert_ok!(ChallengeKindName::Barcode.challenge_kind(&json!({})));
+
+        let first = challenge_kind.generate_challenge(1);
+        let second = challenge_kind.generate_challenge(2);
+
+        assert_ne!(first.challenge, second.challenge);
+    }
+
+    #[test]
+    fn unknown_generator_versions_are_rejected() {
+        let name = ChallengeKindName::Barcode;
+        let parameters = json!({});
+        let version = assert_ok!(name.challenge_kind(&parameters)).version();
+
+        assert_err!(name.regenerate_challenge(42, version + 1, &parameters));
+    }
+}
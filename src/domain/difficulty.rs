@@ -0,0 +1,51 @@
+/// How hard a challenge is. Each tier's generation parameters are configured
+/// under `challenge.difficulties`.
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::Display,
+    strum::EnumString,
+    strum::EnumIter,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    /// What every applicant was issued before tiers existed.
+    #[default]
+    Standard,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn parse(s: &str) -> Result<Difficulty, String> {
+        s.parse()
+            .map_err(|_| format!("Invalid difficulty! Given: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::Difficulty;
+    use claims::{assert_err, assert_ok_eq};
+
+    #[test]
+    fn difficulties_round_trip() {
+        for difficulty in [Difficulty::Easy, Difficulty::Standard, Difficulty::Hard] {
+            assert_ok_eq!(Difficulty::parse(&difficulty.to_string()), difficulty);
+        }
+    }
+
+    #[test]
+    fn unknown_difficulties_are_rejected() {
+        assert_err!(Difficulty::parse("extreme"));
+    }
+}
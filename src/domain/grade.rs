@@ -0,0 +1,93 @@
+/// Per-case outcome of a submission, which never exposes the expected answers.
+#[derive(Debug, PartialEq)]
+pub struct Grade {
+    pub cases_passed: usize,
+    pub cases_total: usize,
+    pub failed_cases: Vec<usize>,
+    /// Answers submitted beyond the number of cases in the challenge.
+    pub extra_answers: usize,
+}
+
+impl Grade {
+    pub fn compute(expected: &[String], submitted: &[String]) -> Grade {
+        let failed_cases = expected
+            .iter()
+            .enumerate()
+            .filter(|(i, answer)| submitted.get(*i) != Some(*answer))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        Grade {
+            cases_passed: expected.len() - failed_cases.len(),
+            cases_total: expected.len(),
+            failed_cases,
+            extra_answers: submitted.len().saturating_sub(expected.len()),
+        }
+    }
+
+    pub fn score(&self) -> f64 {
+        if self.cases_total == 0 {
+            0.0
+        } else {
+            self.cases_passed as f64 / self.cases_total as f64
+        }
+    }
+
+    pub fn is_correct(&self) -> bool {
+        self.failed_cases.is_empty() && self.extra_answers == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::Grade;
+
+    fn answers(answers: &[&str]) -> Vec<String> {
+        answers.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn a_matching_submission_passes_every_case() {
+        let grade = Grade::compute(&answers(&["1", "2", "3"]), &answers(&["1", "2", "3"]));
+
+        assert!(grade.is_correct());
+        assert_eq!(grade.cases_passed, 3);
+        assert_eq!(grade.score(), 1.0);
+    }
+
+    #[test]
+    fn mismatched_cases_are_reported_by_index() {
+        let grade = Grade::compute(
+            &answers(&["1", "2", "3", "4"]),
+            &answers(&["1", "0", "3", "0"]),
+        );
+
+        assert!(!grade.is_correct());
+        assert_eq!(grade.cases_passed, 2);
+        assert_eq!(grade.failed_cases, vec![1, 3]);
+        assert_eq!(grade.score(), 0.5);
+    }
+
+    #[test]
+    fn missing_answers_fail_their_cases() {
+        let grade = Grade::compute(&answers(&["1", "2", "3"]), &answers(&["1"]));
+
+        assert_eq!(grade.failed_cases, vec![1, 2]);
+    }
+
+    #[test]
+    fn extra_answers_make_a_submission_incorrect() {
+        let grade = Grade::compute(&answers(&["1", "2"]), &answers(&["1", "2", "3"]));
+
+        assert!(grade.failed_cases.is_empty());
+        assert!(!grade.is_correct());
+    }
+
+    #[test]
+    fn an_empty_submission_scores_zero() {
+        let grade = Grade::compute(&answers(&["1", "2"]), &[]);
+
+        assert_eq!(grade.cases_passed, 0);
+        assert_eq!(grade.score(), 0.0);
+    }
+}
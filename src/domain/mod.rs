@@ -1,9 +1,27 @@
 pub mod algo_question;
+mod applicant_email;
+mod applicant_listing;
 mod applicant_name;
+mod applicant_token;
+mod attempt_policy;
+mod challenge_kind;
+mod difficulty;
+mod grade;
 mod nuid;
+mod recovery_code;
 mod register_applicant;
+mod scoring_policy;
 
-pub use algo_question::{generate_challenge, parse_barcode};
+pub use algo_question::{parse_barcode, trace_barcode, Barcode, TraceStep};
+pub use applicant_email::ApplicantEmail;
+pub use applicant_listing::{ApplicantCursor, ApplicantSort};
 pub use applicant_name::ApplicantName;
+pub use applicant_token::ApplicantToken;
+pub use attempt_policy::{AttemptPolicy, AttemptRejection, PreviousAttempts};
+pub use challenge_kind::{Challenge, ChallengeKind, ChallengeKindName};
+pub use difficulty::Difficulty;
+pub use grade::Grade;
 pub use nuid::Nuid;
+pub use recovery_code::RecoveryCode;
 pub use register_applicant::RegisterApplicant;
+pub use scoring_policy::ScoringPolicy;
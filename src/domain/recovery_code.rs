@@ -0,0 +1,117 @@
+use crate::domain::Nuid;
+
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+
+const RECOVERY_CODE_LENGTH: usize = 6;
+
+/// A one-time code emailed to an applicant to prove they own the email on
+/// file before their token is revealed.
+#[derive(Debug, PartialEq)]
+pub struct RecoveryCode(String);
+
+impl std::fmt::Display for RecoveryCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl RecoveryCode {
+    pub fn generate(rng: &mut impl Rng) -> RecoveryCode {
+        Self(
+            (0..RECOVERY_CODE_LENGTH)
+                .map(|_| char::from(b'0' + rng.gen_range(0..10)))
+                .collect(),
+        )
+    }
+
+    pub fn parse(s: &str) -> Result<RecoveryCode, String> {
+        let s = s.trim();
+
+        if s.len() == RECOVERY_CODE_LENGTH && s.chars().all(|c| c.is_ascii_digit()) {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(format!("Invalid recovery code! Given: {}", s))
+        }
+    }
+
+    /// HMAC-SHA256 of the code keyed by the server secret and bound to the
+    /// NUID. There are only a million codes, so a plain digest could be
+    /// reversed by trying them all; without the secret it cannot.
+    pub fn hash(&self, nuid: &Nuid, secret: &Secret<String>) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC can take a key of any size");
+        mac.update(nuid.as_ref().as_bytes());
+        mac.update(b":");
+        mac.update(self.0.as_bytes());
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{Nuid, RecoveryCode};
+    use claims::{assert_err, assert_ok};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use secrecy::Secret;
+
+    #[test]
+    fn generated_codes_parse() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let code = RecoveryCode::generate(&mut rng);
+            assert_ok!(RecoveryCode::parse(&code.to_string()));
+        }
+    }
+
+    #[test]
+    fn surrounding_whitespace_is_ignored() {
+        assert_ok!(RecoveryCode::parse(" 012345\n"));
+    }
+
+    #[test]
+    fn codes_of_the_wrong_length_are_rejected() {
+        assert_err!(RecoveryCode::parse("12345"));
+        assert_err!(RecoveryCode::parse("1234567"));
+    }
+
+    #[test]
+    fn non_digit_codes_are_rejected() {
+        assert_err!(RecoveryCode::parse("12a456"));
+    }
+
+    #[test]
+    fn equal_codes_hash_equally() {
+        let nuid = Nuid::parse("001234567").unwrap();
+        let secret = Secret::new("secret".to_string());
+
+        assert_eq!(
+            RecoveryCode::parse("123456").unwrap().hash(&nuid, &secret),
+            RecoveryCode::parse("123456").unwrap().hash(&nuid, &secret)
+        );
+        assert_ne!(
+            RecoveryCode::parse("123456").unwrap().hash(&nuid, &secret),
+            RecoveryCode::parse("654321").unwrap().hash(&nuid, &secret)
+        );
+    }
+
+    #[test]
+    fn hashes_depend_on_the_nuid_and_the_secret() {
+        let code = RecoveryCode::parse("123456").unwrap();
+        let nuid = Nuid::parse("001234567").unwrap();
+        let secret = Secret::new("secret".to_string());
+
+        assert_ne!(
+            code.hash(&nuid, &secret),
+            code.hash(&Nuid::parse("001234568").unwrap(), &secret)
+        );
+        assert_ne!(
+            code.hash(&nuid, &secret),
+            code.hash(&nuid, &Secret::new("other".to_string()))
+        );
+    }
+}
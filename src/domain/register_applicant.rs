@@ -1,8 +1,13 @@
+use crate::domain::ApplicantEmail;
 use crate::domain::ApplicantName;
+use crate::domain::Difficulty;
 use crate::domain::Nuid;
 
 #[derive(serde::Serialize)]
 pub struct RegisterApplicant {
     pub name: ApplicantName,
     pub nuid: Nuid,
+    pub email: ApplicantEmail,
+    /// Absent when the configured default applies.
+    pub difficulty: Option<Difficulty>,
 }
@@ -0,0 +1,25 @@
+/// Which of an applicant's submissions is reported when reviewing them. The
+/// `reported_submission` SQL function picks it by the snake_case name.
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    strum::Display,
+    strum::EnumString,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ScoringPolicy {
+    /// The most recent submission.
+    #[default]
+    Latest,
+    /// The earliest correct submission, or the latest if none is correct.
+    FirstCorrect,
+    /// The highest scoring submission, earliest first on ties.
+    BestScore,
+}
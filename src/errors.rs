@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use chrono::{DateTime, Utc};
+
+use crate::domain::AttemptRejection;
+
+/// The body of every error response.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+pub struct ErrorResponseData {
+    /// Stable, machine readable identifier of the error.
+    pub code: String,
+    pub message: String,
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    Validation(String),
+    Unauthorized(String),
+    TokenRevoked(DateTime<Utc>),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    /// The endpoint was retired; the message says what replaced it.
+    Gone(String),
+    Expired(DateTime<Utc>),
+    AttemptsExhausted,
+    Cooldown {
+        retry_after: Duration,
+    },
+    RateLimited {
+        retry_after: Duration,
+    },
+    /// Never shown to the client; the message is only logged.
+    Unexpected(String),
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::Validation(_) => "validation_error",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::TokenRevoked(_) => "token_revoked",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Gone(_) => "gone",
+            ApiError::Expired(_) => "challenge_expired",
+            ApiError::AttemptsExhausted => "attempts_exhausted",
+            ApiError::Cooldown { .. } => "submission_cooldown",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::Unexpected(_) => "internal_error",
+        }
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ApiError::Expired(deadline) => Some(serde_json::json!({ "deadline": deadline })),
+            ApiError::TokenRevoked(revoked_at) => {
+                Some(serde_json::json!({ "revoked_at": revoked_at }))
+            }
+            ApiError::Cooldown { retry_after } | ApiError::RateLimited { retry_after } => {
+                Some(serde_json::json!({
+                    "retry_after_seconds": retry_after.as_secs()
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Validation(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::Gone(message) => write!(f, "{}", message),
+            ApiError::Expired(deadline) => write!(f, "Challenge expired at {}!", deadline),
+            ApiError::TokenRevoked(revoked_at) => write!(
+                f,
+                "Token was revoked at {}! Use the forgot-token endpoint to recover a new token.",
+                revoked_at
+            ),
+            ApiError::AttemptsExhausted => {
+                write!(f, "{}", AttemptRejection::Exhausted)
+            }
+            ApiError::Cooldown { retry_after } => write!(
+                f,
+                "{}",
+                AttemptRejection::TooSoon {
+                    retry_after: *retry_after
+                }
+            ),
+            ApiError::RateLimited { retry_after } => write!(
+                f,
+                "Too many requests! Retry after {} seconds.",
+                retry_after.as_secs()
+            ),
+            ApiError::Unexpected(_) => write!(f, "An unexpected error occurred."),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::Validation(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) | ApiError::TokenRevoked(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) | ApiError::AttemptsExhausted => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Gone(_) | ApiError::Expired(_) => StatusCode::GONE,
+            ApiError::Cooldown { .. } | ApiError::RateLimited { .. } => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
+            ApiError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+
+        match self {
+            ApiError::Unauthorized(_) => {
+                response.insert_header((
+                    header::WWW_AUTHENTICATE,
+                    HeaderValue::from_static(r#"Bearer realm="admin""#),
+                ));
+            }
+            ApiError::Cooldown { retry_after } | ApiError::RateLimited { retry_after } => {
+                response.insert_header((header::RETRY_AFTER, retry_after.as_secs().to_string()));
+            }
+            _ => (),
+        }
+
+        response.json(ErrorResponseData {
+            code: self.code().to_string(),
+            message: self.to_string(),
+            details: self.details(),
+        })
+    }
+}
+
+/// Rounds `wait` up to whole seconds, the resolution of `Retry-After`, so a
+/// client honouring the header is never early.
+pub fn retry_after(wait: Duration) -> Duration {
+    Duration::from_secs(wait.as_secs() + u64::from(wait.subsec_nanos() > 0))
+}
+
+impl From<AttemptRejection> for ApiError {
+    fn from(rejection: AttemptRejection) -> Self {
+        match rejection {
+            AttemptRejection::Exhausted => ApiError::AttemptsExhausted,
+            AttemptRejection::TooSoon { retry_after } => ApiError::Cooldown { retry_after },
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        tracing::error!("Failed to execute query: {:?}", e);
+        ApiError::Unexpected(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::{ApiError, ErrorResponseData};
+    use actix_web::body::to_bytes;
+    use actix_web::ResponseError;
+
+    #[tokio::test]
+    async fn unexpected_errors_do_not_leak_their_cause() {
+        let response = ApiError::Unexpected("connection refused".to_string()).error_response();
+
+        assert_eq!(500, response.status().as_u16());
+
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let body: ErrorResponseData = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body.code, "internal_error");
+        assert!(!body.message.contains("connection refused"));
+    }
+
+    #[tokio::test]
+    async fn cooldowns_set_retry_after() {
+        let response = ApiError::Cooldown {
+            retry_after: std::time::Duration::from_secs(30),
+        }
+        .error_response();
+
+        assert_eq!(429, response.status().as_u16());
+        assert_eq!("30", response.headers().get("Retry-After").unwrap());
+
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let body: ErrorResponseData = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body.code, "submission_cooldown");
+        assert_eq!(body.details.unwrap()["retry_after_seconds"], 30);
+    }
+
+    #[test]
+    fn exhausted_attempts_are_not_retryable() {
+        let response = ApiError::AttemptsExhausted.error_response();
+
+        assert_eq!(403, response.status().as_u16());
+        assert!(response.headers().get("Retry-After").is_none());
+    }
+}
@@ -1,5 +1,9 @@
+pub mod authentication;
 pub mod configuration;
 pub mod domain;
+pub mod errors;
+pub mod mailer;
+pub mod rate_limiting;
 pub mod routes;
 pub mod startup;
 pub mod telemetry;
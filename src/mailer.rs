@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use secrecy::ExposeSecret;
+
+use crate::configuration::{EmailSettings, EmailTransport};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers emails to applicants.
+#[async_trait::async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), String>;
+}
+
+impl EmailSettings {
+    pub fn mailer(&self) -> Result<Arc<dyn Mailer>, String> {
+        let sender = self
+            .sender
+            .parse::<Mailbox>()
+            .map_err(|e| format!("Invalid sender email address! {}", e))?;
+
+        match &self.transport {
+            EmailTransport::Smtp {
+                host,
+                port,
+                username,
+                password,
+            } => {
+                let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                    .map_err(|e| format!("Invalid SMTP relay! {}", e))?
+                    .port(*port)
+                    .credentials(Credentials::new(
+                        username.clone(),
+                        password.expose_secret().clone(),
+                    ))
+                    .build();
+                Ok(Arc::new(SmtpMailer { sender, transport }))
+            }
+            EmailTransport::File { directory } => Ok(Arc::new(FileMailer {
+                directory: PathBuf::from(directory),
+            })),
+        }
+    }
+}
+
+pub struct SmtpMailer {
+    sender: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+#[async_trait::async_trait]
+impl Mailer for SmtpMailer {
+    #[tracing::instrument(name = "Sending email over SMTP.", skip(self, email))]
+    async fn send(&self, email: Email) -> Result<(), String> {
+        let to = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| format!("Invalid recipient email address! {}", e))?;
+
+        let message = Message::builder()
+            .from(self.sender.clone())
+            .to(to)
+            .subject(email.subject)
+            .body(email.body)
+            .map_err(|e| format!("Failed to build email! {}", e))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to send email! {}", e))
+    }
+}
+
+/// Writes every email to its own file instead of sending it, for local development.
+pub struct FileMailer {
+    directory: PathBuf,
+}
+
+#[async_trait::async_trait]
+impl Mailer for FileMailer {
+    #[tracing::instrument(name = "Writing email to disk.", skip(self, email))]
+    async fn send(&self, email: Email) -> Result<(), String> {
+        tokio::fs::create_dir_all(&self.directory)
+            .await
+            .map_err(|e| format!("Failed to create email directory! {}", e))?;
+
+        let path = self.directory.join(format!("{}.txt", uuid::Uuid::new_v4()));
+        let contents = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            email.to, email.subject, email.body
+        );
+
+        tokio::fs::write(&path, contents)
+            .await
+            .map_err(|e| format!("Failed to write email to {}! {}", path.display(), e))
+    }
+}
+
+/// Keeps every email in memory so tests can read what would have been sent.
+#[derive(Default)]
+pub struct InMemoryMailer {
+    sent: Mutex<Vec<Email>>,
+}
+
+impl InMemoryMailer {
+    pub fn sent(&self) -> Vec<Email> {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for InMemoryMailer {
+    async fn send(&self, email: Email) -> Result<(), String> {
+        self.sent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(email);
+        Ok(())
+    }
+}
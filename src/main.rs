@@ -1,5 +1,8 @@
 use std::net::TcpListener;
 
+use generate_coding_challenge_server::authentication::{
+    hash_stored_tokens, store_admin_api_keys, HmacSecret,
+};
 use generate_coding_challenge_server::configuration::get_configuration;
 use generate_coding_challenge_server::startup::run;
 use generate_coding_challenge_server::telemetry::{get_subscriber, init_subscriber};
@@ -20,12 +23,25 @@ async fn main() -> std::io::Result<()> {
         .connect_with(configuration.database.with_db())
         .await
         .expect("Failed to connect to Postgres.");
+    store_admin_api_keys(&connection_pool, &configuration.admin)
+        .await
+        .expect("Failed to store admin API keys.");
+    hash_stored_tokens(
+        &connection_pool,
+        &HmacSecret(configuration.application.hmac_secret.clone()),
+    )
+    .await
+    .expect("Failed to hash stored applicant tokens.");
 
     let address = format!(
         "{}:{}",
         configuration.application.host, configuration.application.port
     );
     let listener = TcpListener::bind(address)?;
-    run(listener, connection_pool)?.await?;
+    let mailer = configuration
+        .email
+        .mailer()
+        .expect("Failed to configure the mailer.");
+    run(listener, connection_pool, configuration, mailer)?.await?;
     Ok(())
 }
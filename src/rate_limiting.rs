@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use actix_web::HttpRequest;
+
+use crate::configuration::{BucketSettings, RateLimitSettings};
+use crate::domain::Nuid;
+use crate::errors::{retry_after, ApiError};
+
+/// Past this many tracked keys, buckets that have refilled completely are
+/// forgotten, so the limiter cannot grow without bound.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// A token bucket per key, refilled continuously at a fixed rate.
+pub struct TokenBuckets {
+    settings: BucketSettings,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl TokenBuckets {
+    pub fn new(settings: BucketSettings) -> Self {
+        Self {
+            settings,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from `key`'s bucket, or returns how long until one is available.
+    pub fn take(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.settings.capacity);
+        let refill_seconds = self.settings.refill_seconds as f64;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_KEYS {
+            buckets.retain(|_, bucket| {
+                let elapsed = now.saturating_duration_since(bucket.last_refill);
+                bucket.tokens + elapsed.as_secs_f64() / refill_seconds < capacity
+            });
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() / refill_seconds).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) * refill_seconds;
+            Err(retry_after(Duration::from_secs_f64(wait)))
+        }
+    }
+}
+
+/// Limits how often a single client, and how often a single NUID, may hit the
+/// registration and token recovery endpoints.
+pub struct RateLimiter {
+    trust_forwarded_for: bool,
+    by_ip: TokenBuckets,
+    by_nuid: TokenBuckets,
+}
+
+impl RateLimiter {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        Self {
+            trust_forwarded_for: settings.trust_forwarded_for,
+            by_ip: TokenBuckets::new(settings.per_ip),
+            by_nuid: TokenBuckets::new(settings.per_nuid),
+        }
+    }
+
+    /// Charges the request against its client IP. Called before anything
+    /// else is checked, so failing requests use up the client's budget too.
+    pub fn check_client(&self, req: &HttpRequest) -> Result<(), ApiError> {
+        let ip = match self.client_ip(req) {
+            Some(ip) => ip,
+            None => return Ok(()),
+        };
+
+        self.by_ip
+            .take(&ip.to_string(), Instant::now())
+            .map_err(|retry_after| {
+                tracing::error!("Rate limit exceeded for IP {}", ip);
+                ApiError::RateLimited { retry_after }
+            })
+    }
+
+    /// Charges the request against the NUID it targets.
+    pub fn check_nuid(&self, nuid: &Nuid) -> Result<(), ApiError> {
+        self.by_nuid
+            .take(nuid.as_ref(), Instant::now())
+            .map_err(|retry_after| {
+                tracing::error!("Rate limit exceeded for NUID {}", nuid);
+                ApiError::RateLimited { retry_after }
+            })
+    }
+
+    fn client_ip(&self, req: &HttpRequest) -> Option<IpAddr> {
+        if self.trust_forwarded_for {
+            // The proxy appends the address it saw to whatever the client sent,
+            // so only the right-most entry can be trusted.
+            if let Some(ip) = req
+                .headers()
+                .get_all("X-Forwarded-For")
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .last()
+                .and_then(|addr| addr.trim().parse().ok())
+            {
+                return Some(ip);
+            }
+        }
+
+        req.peer_addr().map(|addr| addr.ip())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::BucketSettings;
+    use crate::rate_limiting::TokenBuckets;
+    use claims::{assert_err_eq, assert_ok};
+    use std::time::{Duration, Instant};
+
+    fn buckets(capacity: u32, refill_seconds: u64) -> TokenBuckets {
+        TokenBuckets::new(BucketSettings {
+            capacity,
+            refill_seconds,
+        })
+    }
+
+    #[test]
+    fn a_full_bucket_allows_a_burst_up_to_its_capacity() {
+        let buckets = buckets(3, 60);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_ok!(buckets.take("key", now));
+        }
+        assert_err_eq!(buckets.take("key", now), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn buckets_refill_over_time() {
+        let buckets = buckets(1, 10);
+        let now = Instant::now();
+
+        assert_ok!(buckets.take("key", now));
+        assert_err_eq!(
+            buckets.take("key", now + Duration::from_millis(2_500)),
+            Duration::from_secs(8)
+        );
+        assert_ok!(buckets.take("key", now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn keys_have_independent_buckets() {
+        let buckets = buckets(1, 60);
+        let now = Instant::now();
+
+        assert_ok!(buckets.take("first", now));
+        assert_ok!(buckets.take("second", now));
+        assert!(buckets.take("first", now).is_err());
+    }
+}
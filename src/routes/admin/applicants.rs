@@ -0,0 +1,329 @@
+use std::time::Duration;
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::{query_as, PgPool};
+
+use crate::authentication::AdminUser;
+use crate::configuration::ScoringSettings;
+use crate::domain::{ApplicantCursor, ApplicantSort, Difficulty, ScoringPolicy};
+use crate::errors::ApiError;
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListApplicantsQuery {
+    /// Only applicants who registered at or after this time.
+    pub registered_after: Option<DateTime<Utc>>,
+    /// Only applicants who registered before this time.
+    pub registered_before: Option<DateTime<Utc>>,
+    /// Only applicants who have, or have not, submitted.
+    pub submitted: Option<bool>,
+    /// Only applicants whose reported submission is, or is not, correct.
+    pub correct: Option<bool>,
+    /// Only applicants issued a challenge at this difficulty.
+    #[param(inline)]
+    pub difficulty: Option<Difficulty>,
+    /// Picks the submission each row reports on, defaulting to `scoring.default_policy`.
+    #[param(inline)]
+    pub scoring: Option<ScoringPolicy>,
+    #[param(inline)]
+    pub sort: Option<ApplicantSort>,
+    /// Applicants per page; 50 by default and at most 200.
+    pub limit: Option<u32>,
+    /// 1-based page number. Cannot be combined with `cursor`.
+    pub page: Option<u32>,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ListedApplicant {
+    pub nuid: String,
+    pub name: String,
+    pub registration_time: DateTime<Utc>,
+    pub deadline: Option<DateTime<Utc>>,
+    /// The difficulty the applicant's challenge was issued at.
+    pub difficulty: String,
+    pub submitted: bool,
+    /// Absent for applicants who have not submitted.
+    pub correct: Option<bool>,
+    pub score: Option<f64>,
+    /// Serialized as `{ "secs": .., "nanos": .. }`.
+    #[schema(value_type = Option<Object>)]
+    pub time_to_completion: Option<Duration>,
+    /// Set when the reported submission only made it in during the grace period.
+    pub late: Option<bool>,
+    /// The known bug that best explains an incorrect reported submission, if any.
+    pub misconception: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ListApplicantsResponseData {
+    /// The policy that picked each applicant's reported submission.
+    pub scoring: ScoringPolicy,
+    pub applicants: Vec<ListedApplicant>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+pub struct ApplicantFilters {
+    pub registered_after: Option<DateTime<Utc>>,
+    pub registered_before: Option<DateTime<Utc>>,
+    pub submitted: Option<bool>,
+    pub correct: Option<bool>,
+    pub difficulty: Option<Difficulty>,
+    pub scoring: ScoringPolicy,
+    pub sort: ApplicantSort,
+}
+
+pub struct ApplicantPage {
+    pub limit: u32,
+    pub offset: u32,
+    pub after: Option<ApplicantCursor>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/applicants",
+    params(ListApplicantsQuery),
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "A page of the applicants matching the filters.", body = ListApplicantsResponseData),
+        (status = 400, description = "Invalid filters or pagination.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Listing applicants.",
+    skip(_admin, query, pool, scoring_settings)
+)]
+pub async fn list_applicants(
+    _admin: AdminUser,
+    query: web::Query<ListApplicantsQuery>,
+    pool: web::Data<PgPool>,
+    scoring_settings: web::Data<ScoringSettings>,
+) -> Result<HttpResponse, ApiError> {
+    let query = query.into_inner();
+    let sort = query.sort.unwrap_or_default();
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        tracing::error!("Invalid limit! Given: {}", limit);
+        return Err(ApiError::Validation(format!(
+            "Invalid limit! Given: {}, expected between 1 and {}",
+            limit, MAX_LIMIT
+        )));
+    }
+
+    let page = match (query.page, query.cursor) {
+        (Some(_), Some(_)) => {
+            tracing::error!("Both page and cursor given");
+            return Err(ApiError::Validation(
+                "Give either page or cursor, not both!".to_string(),
+            ));
+        }
+        (Some(0), None) => {
+            tracing::error!("Invalid page! Given: 0");
+            return Err(ApiError::Validation(
+                "Invalid page! Given: 0, pages start at 1".to_string(),
+            ));
+        }
+        (Some(page), None) => ApplicantPage {
+            limit,
+            offset: (page - 1).saturating_mul(limit),
+            after: None,
+        },
+        (None, Some(cursor)) => match ApplicantCursor::parse(&cursor) {
+            Ok(cursor) if cursor.sort == sort => ApplicantPage {
+                limit,
+                offset: 0,
+                after: Some(cursor),
+            },
+            Ok(cursor) => {
+                tracing::error!("Cursor for sort {} used with sort {}", cursor.sort, sort);
+                return Err(ApiError::Validation(format!(
+                    "Cursor was issued for sort {}, not {}!",
+                    cursor.sort, sort
+                )));
+            }
+            Err(err) => {
+                tracing::error!(err);
+                return Err(ApiError::Validation(err));
+            }
+        },
+        (None, None) => ApplicantPage {
+            limit,
+            offset: 0,
+            after: None,
+        },
+    };
+
+    let filters = ApplicantFilters {
+        registered_after: query.registered_after,
+        registered_before: query.registered_before,
+        submitted: query.submitted,
+        correct: query.correct,
+        difficulty: query.difficulty,
+        scoring: query.scoring.unwrap_or(scoring_settings.default_policy),
+        sort,
+    };
+
+    let (applicants, next_cursor) = select_applicant_page(&pool, &filters, &page).await?;
+
+    Ok(HttpResponse::Ok().json(ListApplicantsResponseData {
+        scoring: filters.scoring,
+        applicants,
+        next_cursor: next_cursor.map(|cursor| cursor.to_string()),
+    }))
+}
+
+/// A row of the applicant listing, with the key it was sorted on.
+struct ApplicantRecord {
+    nuid: String,
+    applicant_name: String,
+    registration_time: DateTime<Utc>,
+    deadline: Option<DateTime<Utc>>,
+    difficulty: String,
+    correct: Option<bool>,
+    score: Option<f64>,
+    submission_time: Option<DateTime<Utc>>,
+    late: Option<bool>,
+    misconception: Option<String>,
+    sort_key: i64,
+}
+
+#[tracing::instrument(
+    name = "Fetching a page of applicants from the database.",
+    skip(pool, filters, page)
+)]
+pub async fn select_applicant_page(
+    pool: &PgPool,
+    filters: &ApplicantFilters,
+    page: &ApplicantPage,
+) -> Result<(Vec<ListedApplicant>, Option<ApplicantCursor>), sqlx::Error> {
+    // One extra row tells whether there is a next page.
+    let limit = i64::from(page.limit) + 1;
+    let offset = i64::from(page.offset);
+    let after_nuid = page.after.as_ref().map(|cursor| cursor.nuid.as_str());
+
+    let mut records = match filters.sort {
+        // Orders and seeks on the columns of `applicants_registration_time_idx`
+        // so a page is read straight off the index.
+        ApplicantSort::RegistrationTime => {
+            query_as!(
+                ApplicantRecord,
+                r#"SELECT a.nuid, a.applicant_name, a.registration_time, a.deadline, a.difficulty,
+                reported.correct AS "correct?", reported.score,
+                reported.submission_time AS "submission_time?", reported.late AS "late?",
+                reported.misconception,
+                (EXTRACT(EPOCH FROM a.registration_time) * 1000000)::bigint AS "sort_key!"
+                FROM applicants a
+                LEFT JOIN LATERAL reported_submission(a.nuid, $9) reported ON true
+                WHERE ($1::timestamptz IS NULL OR a.registration_time >= $1)
+                AND ($2::timestamptz IS NULL OR a.registration_time < $2)
+                AND ($3::bool IS NULL OR (reported.submission_time IS NOT NULL) = $3)
+                AND ($4::bool IS NULL OR reported.correct = $4)
+                AND ($10::text IS NULL OR a.difficulty = $10)
+                AND ($5::timestamptz IS NULL OR (a.registration_time, a.nuid) > ($5, $6::varchar))
+                ORDER BY a.registration_time, a.nuid
+                LIMIT $7 OFFSET $8;"#,
+                filters.registered_after,
+                filters.registered_before,
+                filters.submitted,
+                filters.correct,
+                page.after
+                    .as_ref()
+                    .map(|cursor| Utc.timestamp_nanos(cursor.sort_key.saturating_mul(1000))),
+                after_nuid,
+                limit,
+                offset,
+                filters.scoring.to_string(),
+                filters.difficulty.map(|difficulty| difficulty.to_string()),
+            )
+            .fetch_all(pool)
+            .await?
+        }
+        // Time to completion depends on the reported submission, so no index
+        // can serve this order: every matching applicant is read and sorted.
+        // Sort keys are microseconds, so the cursor compares exactly.
+        // Applicants who have not submitted sort last.
+        ApplicantSort::TimeToCompletion => {
+            query_as!(
+                ApplicantRecord,
+                r#"SELECT nuid AS "nuid!", applicant_name AS "applicant_name!",
+                registration_time AS "registration_time!", deadline, difficulty AS "difficulty!",
+                correct AS "correct?", score, submission_time AS "submission_time?", late AS "late?",
+                misconception, sort_key AS "sort_key!" FROM (
+                    SELECT a.nuid, a.applicant_name, a.registration_time, a.deadline, a.difficulty,
+                    reported.correct, reported.score, reported.submission_time,
+                    reported.misconception, reported.late,
+                    COALESCE(
+                        (EXTRACT(EPOCH FROM reported.submission_time - a.registration_time) * 1000000)::bigint,
+                        9223372036854775807
+                    ) AS sort_key
+                    FROM applicants a
+                    LEFT JOIN LATERAL reported_submission(a.nuid, $9) reported ON true
+                    WHERE ($1::timestamptz IS NULL OR a.registration_time >= $1)
+                    AND ($2::timestamptz IS NULL OR a.registration_time < $2)
+                    AND ($3::bool IS NULL OR (reported.submission_time IS NOT NULL) = $3)
+                    AND ($4::bool IS NULL OR reported.correct = $4)
+                    AND ($10::text IS NULL OR a.difficulty = $10)
+                ) listed
+                WHERE ($5::bigint IS NULL OR (sort_key, nuid) > ($5, $6::varchar))
+                ORDER BY sort_key, nuid
+                LIMIT $7 OFFSET $8;"#,
+                filters.registered_after,
+                filters.registered_before,
+                filters.submitted,
+                filters.correct,
+                page.after.as_ref().map(|cursor| cursor.sort_key),
+                after_nuid,
+                limit,
+                offset,
+                filters.scoring.to_string(),
+                filters.difficulty.map(|difficulty| difficulty.to_string()),
+            )
+            .fetch_all(pool)
+            .await?
+        }
+    };
+
+    let next_cursor = if records.len() > page.limit as usize {
+        records.truncate(page.limit as usize);
+        records.last().map(|record| ApplicantCursor {
+            sort: filters.sort,
+            sort_key: record.sort_key,
+            nuid: record.nuid.clone(),
+        })
+    } else {
+        None
+    };
+
+    let applicants = records
+        .into_iter()
+        .map(|record| ListedApplicant {
+            submitted: record.submission_time.is_some(),
+            time_to_completion: record.submission_time.map(|submission_time| {
+                submission_time
+                    .signed_duration_since(record.registration_time)
+                    .to_std()
+                    .unwrap_or(Duration::from_secs(0))
+            }),
+            late: record.late,
+            nuid: record.nuid,
+            name: record.applicant_name,
+            registration_time: record.registration_time,
+            deadline: record.deadline,
+            difficulty: record.difficulty,
+            correct: record.correct,
+            score: record.score,
+            misconception: record.misconception,
+        })
+        .collect();
+
+    Ok((applicants, next_cursor))
+}
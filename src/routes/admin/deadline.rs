@@ -0,0 +1,119 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{query, PgPool};
+
+use crate::authentication::AdminUser;
+use crate::domain::Nuid;
+use crate::errors::ApiError;
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ExtendDeadlineBodyData {
+    pub deadline: DateTime<Utc>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ExtendDeadlineResponseData {
+    pub nuid: String,
+    pub deadline: DateTime<Utc>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/admin/applicants/{nuid}/deadline",
+    params(("nuid" = String, Path, description = "NUID of the applicant.")),
+    request_body = ExtendDeadlineBodyData,
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "The deadline was extended.", body = ExtendDeadlineResponseData),
+        (status = 400, description = "Invalid NUID or a deadline that is not later.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+        (status = 404, description = "No applicant registered with the NUID.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Extending applicant deadline.",
+    skip(_admin, nuid, body, pool),
+    fields(
+        applicant_nuid = %nuid,
+        deadline = %body.deadline
+    )
+)]
+pub async fn extend_deadline(
+    _admin: AdminUser,
+    nuid: web::Path<String>,
+    body: web::Json<ExtendDeadlineBodyData>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, ApiError> {
+    let nuid = match Nuid::parse(&nuid) {
+        Ok(nuid) => nuid,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("Failed to begin transaction: {:?}", e);
+            return Err(ApiError::Unexpected(e.to_string()));
+        }
+    };
+
+    let current_deadline = match query!(
+        r#"SELECT deadline FROM applicants WHERE nuid=$1 FOR UPDATE"#,
+        nuid.as_ref()
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    {
+        Ok(record) => record.deadline,
+        Err(sqlx::Error::RowNotFound) => {
+            tracing::error!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            );
+            return Err(ApiError::NotFound(format!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if let Some(current_deadline) = current_deadline {
+        if body.deadline <= current_deadline {
+            tracing::error!(
+                "New deadline {} is not after the current deadline {}",
+                body.deadline,
+                current_deadline
+            );
+            return Err(ApiError::Validation(format!(
+                "New deadline must be later than the current deadline! Current: {}",
+                current_deadline
+            )));
+        }
+    }
+
+    if let Err(e) = query!(
+        r#"UPDATE applicants SET deadline=$2 WHERE nuid=$1"#,
+        nuid.as_ref(),
+        body.deadline
+    )
+    .execute(&mut *transaction)
+    .await
+    {
+        return Err(e.into());
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("Failed to commit transaction: {:?}", e);
+        return Err(ApiError::Unexpected(e.to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(ExtendDeadlineResponseData {
+        nuid: nuid.to_string(),
+        deadline: body.deadline,
+    }))
+}
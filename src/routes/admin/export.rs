@@ -0,0 +1,359 @@
+use actix_web::http::header::{self, ContentDisposition, DispositionParam, DispositionType};
+use actix_web::web::Bytes;
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use futures_util::stream;
+use rust_xlsxwriter::Workbook;
+use sqlx::{query, PgPool};
+
+use crate::authentication::AdminUser;
+use crate::configuration::ScoringSettings;
+use crate::domain::ScoringPolicy;
+use crate::errors::ApiError;
+
+/// Applicants fetched from the database at a time while exporting.
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Column order of every export.
+pub const EXPORT_COLUMNS: [&str; 12] = [
+    "nuid",
+    "name",
+    "registration_time",
+    "attempts",
+    "first_submission_time",
+    "first_submission_score",
+    "last_submission_time",
+    "last_submission_score",
+    "best_submission_time",
+    "best_submission_score",
+    "correct",
+    "time_to_completion_seconds",
+];
+
+#[derive(serde::Deserialize, Clone, Copy, Default, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportApplicantsQuery {
+    #[param(inline)]
+    pub format: Option<ExportFormat>,
+    /// Which submission `correct` and `time_to_completion_seconds` report; the
+    /// configured default if absent.
+    #[param(inline)]
+    pub scoring: Option<ScoringPolicy>,
+}
+
+pub struct ExportedSubmission {
+    pub submission_time: DateTime<Utc>,
+    pub score: Option<f64>,
+}
+
+pub struct ExportedApplicant {
+    pub nuid: String,
+    pub name: String,
+    pub registration_time: DateTime<Utc>,
+    pub attempts: i64,
+    pub first_submission: Option<ExportedSubmission>,
+    pub last_submission: Option<ExportedSubmission>,
+    pub best_submission: Option<ExportedSubmission>,
+    /// Fills the `correct` column; empty for applicants who never submitted.
+    pub correct: Option<bool>,
+    /// When the reported submission was made.
+    pub reported_submission_time: Option<DateTime<Utc>>,
+}
+
+pub enum ExportCell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl ExportedApplicant {
+    /// The applicant's cells, in the order of `EXPORT_COLUMNS`.
+    fn cells(&self) -> [ExportCell; 12] {
+        let time = |submission: &Option<ExportedSubmission>| match submission {
+            Some(submission) => ExportCell::Text(submission.submission_time.to_rfc3339()),
+            None => ExportCell::Empty,
+        };
+        let score = |submission: &Option<ExportedSubmission>| match submission
+            .as_ref()
+            .and_then(|submission| submission.score)
+        {
+            Some(score) => ExportCell::Number(score),
+            None => ExportCell::Empty,
+        };
+
+        [
+            ExportCell::Text(self.nuid.clone()),
+            ExportCell::Text(self.name.clone()),
+            ExportCell::Text(self.registration_time.to_rfc3339()),
+            ExportCell::Number(self.attempts as f64),
+            time(&self.first_submission),
+            score(&self.first_submission),
+            time(&self.last_submission),
+            score(&self.last_submission),
+            time(&self.best_submission),
+            score(&self.best_submission),
+            match self.correct {
+                Some(correct) => ExportCell::Text(correct.to_string()),
+                None => ExportCell::Empty,
+            },
+            match self.reported_submission_time {
+                Some(submission_time) => ExportCell::Number(
+                    submission_time
+                        .signed_duration_since(self.registration_time)
+                        .num_seconds() as f64,
+                ),
+                None => ExportCell::Empty,
+            },
+        ]
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/applicants/export",
+    params(ExportApplicantsQuery),
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "Every applicant, ordered by registration time.", content(
+            ("text/csv" = String),
+            ("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" = String),
+        )),
+        (status = 400, description = "Unknown format or scoring policy.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Exporting applicants.",
+    skip(_admin, query, pool, scoring_settings)
+)]
+pub async fn export_applicants(
+    _admin: AdminUser,
+    query: web::Query<ExportApplicantsQuery>,
+    pool: web::Data<PgPool>,
+    scoring_settings: web::Data<ScoringSettings>,
+) -> Result<HttpResponse, ApiError> {
+    let scoring = query.scoring.unwrap_or(scoring_settings.default_policy);
+
+    match query.format.unwrap_or_default() {
+        ExportFormat::Csv => Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header(attachment("applicants.csv"))
+            .streaming(csv_stream(pool.get_ref().clone(), scoring))),
+        ExportFormat::Xlsx => {
+            let workbook = xlsx_workbook(&pool, scoring).await?;
+            Ok(HttpResponse::Ok()
+                .content_type("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+                .insert_header(attachment("applicants.xlsx"))
+                .body(workbook))
+        }
+    }
+}
+
+fn attachment(filename: &str) -> (header::HeaderName, ContentDisposition) {
+    (
+        header::CONTENT_DISPOSITION,
+        ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(filename.to_string())],
+        },
+    )
+}
+
+/// Where the previous batch ended; `None` before the first batch.
+type ExportPosition = Option<(DateTime<Utc>, String)>;
+
+/// Streams the header, then one chunk of rows per batch of applicants, so the
+/// whole cohort is never held in memory.
+fn csv_stream(
+    pool: PgPool,
+    scoring: ScoringPolicy,
+) -> impl futures_util::Stream<Item = Result<Bytes, ApiError>> + 'static {
+    let header = stream::once(async { csv_chunk(&[], true) });
+
+    // The position after the last batch is `None` once every row has been sent.
+    let rows = stream::try_unfold((pool, Some(None)), move |(pool, position)| async move {
+        let after = match position {
+            Some(after) => after,
+            None => return Ok(None),
+        };
+
+        let batch = select_export_batch(&pool, &after, scoring).await?;
+        if batch.is_empty() {
+            return Ok(None);
+        }
+
+        let next = if batch.len() < EXPORT_BATCH_SIZE as usize {
+            None
+        } else {
+            batch
+                .last()
+                .map(|applicant| Some((applicant.registration_time, applicant.nuid.clone())))
+        };
+
+        Ok(Some((csv_chunk(&batch, false)?, (pool, next))))
+    });
+
+    futures_util::StreamExt::chain(header, rows)
+}
+
+fn csv_chunk(applicants: &[ExportedApplicant], with_header: bool) -> Result<Bytes, ApiError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let to_error = |e: csv::Error| {
+        tracing::error!("Failed to write CSV: {:?}", e);
+        ApiError::Unexpected(e.to_string())
+    };
+
+    if with_header {
+        writer.write_record(EXPORT_COLUMNS).map_err(to_error)?;
+    }
+
+    for applicant in applicants {
+        let record = applicant.cells().map(|cell| match cell {
+            ExportCell::Text(text) => spreadsheet_safe(text),
+            ExportCell::Number(number) => number.to_string(),
+            ExportCell::Empty => String::new(),
+        });
+        writer.write_record(&record).map_err(to_error)?;
+    }
+
+    writer
+        .into_inner()
+        .map(Bytes::from)
+        .map_err(|e| to_error(e.into_error().into()))
+}
+
+/// Spreadsheets run cells starting with these as formulas, and names may
+/// start with some of them, so such cells are prefixed to be read as text.
+fn spreadsheet_safe(text: String) -> String {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", text)
+    } else {
+        text
+    }
+}
+
+async fn xlsx_workbook(pool: &PgPool, scoring: ScoringPolicy) -> Result<Vec<u8>, ApiError> {
+    let to_error = |e: rust_xlsxwriter::XlsxError| {
+        tracing::error!("Failed to write XLSX: {:?}", e);
+        ApiError::Unexpected(e.to_string())
+    };
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+
+    for (col, column) in EXPORT_COLUMNS.iter().enumerate() {
+        worksheet
+            .write_string(0, col as u16, *column)
+            .map_err(to_error)?;
+    }
+
+    let mut row = 1;
+    let mut after = None;
+    loop {
+        let batch = select_export_batch(pool, &after, scoring).await?;
+
+        for applicant in &batch {
+            for (col, cell) in applicant.cells().into_iter().enumerate() {
+                // XLSX keeps text and formulas apart, so names need no escaping.
+                match cell {
+                    ExportCell::Text(text) => worksheet.write_string(row, col as u16, text),
+                    ExportCell::Number(number) => worksheet.write_number(row, col as u16, number),
+                    ExportCell::Empty => continue,
+                }
+                .map_err(to_error)?;
+            }
+            row += 1;
+        }
+
+        if batch.len() < EXPORT_BATCH_SIZE as usize {
+            break;
+        }
+        after = batch
+            .last()
+            .map(|applicant| (applicant.registration_time, applicant.nuid.clone()));
+    }
+
+    workbook.save_to_buffer().map_err(to_error)
+}
+
+#[tracing::instrument(
+    name = "Fetching a batch of applicants to export from the database.",
+    skip(pool, after, scoring)
+)]
+pub async fn select_export_batch(
+    pool: &PgPool,
+    after: &ExportPosition,
+    scoring: ScoringPolicy,
+) -> Result<Vec<ExportedApplicant>, sqlx::Error> {
+    // The best submission is the one the best score policy reports, so
+    // submissions graded before scores were recorded rank by correctness.
+    let records = query!(
+        r#"SELECT a.nuid, a.applicant_name, a.registration_time,
+        counted.attempts AS "attempts!",
+        first.submission_time AS "first_submission_time?", first.score AS first_score,
+        last.submission_time AS "last_submission_time?", last.score AS last_score,
+        best.submission_time AS "best_submission_time?", best.score AS best_score,
+        reported.correct AS "reported_correct?",
+        reported.submission_time AS "reported_submission_time?"
+        FROM applicants a
+        CROSS JOIN LATERAL (
+            SELECT COUNT(*) AS attempts FROM submissions WHERE submissions.nuid = a.nuid
+        ) counted
+        LEFT JOIN LATERAL (
+            SELECT submission_time, score FROM submissions WHERE submissions.nuid = a.nuid
+            ORDER BY submission_time LIMIT 1
+        ) first ON true
+        LEFT JOIN LATERAL (
+            SELECT submission_time, score FROM submissions WHERE submissions.nuid = a.nuid
+            ORDER BY submission_time DESC LIMIT 1
+        ) last ON true
+        LEFT JOIN LATERAL reported_submission(a.nuid, $4) best ON true
+        LEFT JOIN LATERAL reported_submission(a.nuid, $5) reported ON true
+        WHERE ($1::timestamptz IS NULL OR (a.registration_time, a.nuid) > ($1, $2::varchar))
+        ORDER BY a.registration_time, a.nuid
+        LIMIT $3;"#,
+        after
+            .as_ref()
+            .map(|(registration_time, _)| *registration_time),
+        after.as_ref().map(|(_, nuid)| nuid.as_str()),
+        EXPORT_BATCH_SIZE,
+        ScoringPolicy::BestScore.to_string(),
+        scoring.to_string(),
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let submission = |submission_time: Option<DateTime<Utc>>, score: Option<f64>| {
+                submission_time.map(|submission_time| ExportedSubmission {
+                    submission_time,
+                    score,
+                })
+            };
+
+            ExportedApplicant {
+                first_submission: submission(record.first_submission_time, record.first_score),
+                last_submission: submission(record.last_submission_time, record.last_score),
+                best_submission: submission(record.best_submission_time, record.best_score),
+                nuid: record.nuid,
+                name: record.applicant_name,
+                registration_time: record.registration_time,
+                attempts: record.attempts,
+                correct: record.reported_correct,
+                reported_submission_time: record.reported_submission_time,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,17 @@
+pub mod applicants;
+pub mod deadline;
+pub mod export;
+pub mod stats;
+pub mod submissions;
+pub mod token;
+pub mod trace;
+pub mod verify;
+
+pub use applicants::list_applicants;
+pub use deadline::extend_deadline;
+pub use export::export_applicants;
+pub use stats::stats;
+pub use submissions::submission_history;
+pub use token::rotate_token;
+pub use trace::trace_case;
+pub use verify::verify_challenge;
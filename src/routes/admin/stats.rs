@@ -0,0 +1,236 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{query, PgPool};
+
+use crate::authentication::AdminUser;
+use crate::configuration::ScoringSettings;
+use crate::domain::ScoringPolicy;
+use crate::errors::ApiError;
+
+const DEFAULT_LEADERBOARD_SIZE: u32 = 10;
+const MAX_LEADERBOARD_SIZE: u32 = 100;
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, strum::Display, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StatsInterval {
+    #[default]
+    Day,
+    Week,
+}
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// Length of the periods registrations are counted over.
+    #[param(inline)]
+    pub interval: Option<StatsInterval>,
+    /// Ranks the leaderboard by each applicant's first correct, latest or best
+    /// submission. Falls back to the server's policy.
+    #[param(inline)]
+    pub scoring: Option<ScoringPolicy>,
+    /// Applicants on the leaderboard; 10 by default and at most 100.
+    pub leaderboard_size: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct RegistrationPeriod {
+    pub period_start: DateTime<Utc>,
+    pub registrations: i64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct AttemptsToFirstCorrect {
+    pub attempts: i64,
+    pub applicants: i64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct LeaderboardEntry {
+    /// Applicants who tie share a rank.
+    pub rank: i64,
+    pub nuid: String,
+    pub name: String,
+    pub correct: bool,
+    pub score: Option<f64>,
+    pub time_to_completion_seconds: f64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct StatsResponseData {
+    pub registrations: i64,
+    /// Applicants with at least one submission.
+    pub submitted: i64,
+    /// Applicants with at least one correct submission.
+    pub correct: i64,
+    /// Share of registered applicants who submitted.
+    pub submission_rate: f64,
+    /// Share of submitting applicants who were correct.
+    pub correctness_rate: f64,
+    /// Over applicants who were correct, up to their first correct submission.
+    pub median_time_to_completion_seconds: Option<f64>,
+    pub p90_time_to_completion_seconds: Option<f64>,
+    pub registrations_over_time: Vec<RegistrationPeriod>,
+    pub attempts_to_first_correct: Vec<AttemptsToFirstCorrect>,
+    pub scoring: ScoringPolicy,
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/stats",
+    params(StatsQuery),
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "Statistics over the whole cohort.", body = StatsResponseData),
+        (status = 400, description = "Invalid interval, scoring policy or leaderboard size.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Computing cohort stats.",
+    skip(_admin, query, pool, scoring_settings)
+)]
+pub async fn stats(
+    _admin: AdminUser,
+    query: web::Query<StatsQuery>,
+    pool: web::Data<PgPool>,
+    scoring_settings: web::Data<ScoringSettings>,
+) -> Result<HttpResponse, ApiError> {
+    let leaderboard_size = query.leaderboard_size.unwrap_or(DEFAULT_LEADERBOARD_SIZE);
+    if leaderboard_size > MAX_LEADERBOARD_SIZE {
+        tracing::error!("Invalid leaderboard size! Given: {}", leaderboard_size);
+        return Err(ApiError::Validation(format!(
+            "Invalid leaderboard size! Given: {}, expected at most {}",
+            leaderboard_size, MAX_LEADERBOARD_SIZE
+        )));
+    }
+    let scoring = query.scoring.unwrap_or(scoring_settings.default_policy);
+
+    let totals = query!(
+        r#"SELECT COUNT(*) AS "registrations!",
+        COUNT(*) FILTER (WHERE EXISTS (
+            SELECT 1 FROM submissions WHERE submissions.nuid = a.nuid
+        )) AS "submitted!",
+        COUNT(*) FILTER (WHERE EXISTS (
+            SELECT 1 FROM submissions WHERE submissions.nuid = a.nuid AND correct
+        )) AS "correct!"
+        FROM applicants a"#
+    )
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    let times = query!(
+        r#"SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY seconds) AS median,
+        percentile_cont(0.9) WITHIN GROUP (ORDER BY seconds) AS p90
+        FROM (
+            SELECT EXTRACT(EPOCH FROM MIN(submission_time) - registration_time)::float8 AS seconds
+            FROM submissions JOIN applicants USING (nuid) WHERE correct
+            GROUP BY nuid, registration_time
+        ) solved"#
+    )
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    let registrations_over_time = query!(
+        r#"SELECT date_trunc($1, registration_time) AS "period_start!",
+        COUNT(*) AS "registrations!"
+        FROM applicants GROUP BY 1 ORDER BY 1"#,
+        query.interval.unwrap_or_default().to_string()
+    )
+    .fetch_all(pool.get_ref())
+    .await?
+    .into_iter()
+    .map(|record| RegistrationPeriod {
+        period_start: record.period_start,
+        registrations: record.registrations,
+    })
+    .collect();
+
+    let attempts_to_first_correct = query!(
+        r#"SELECT attempts AS "attempts!", COUNT(*) AS "applicants!" FROM (
+            SELECT COUNT(*) AS attempts FROM submissions
+            JOIN (
+                SELECT nuid, MIN(submission_time) AS first_correct_time FROM submissions
+                WHERE correct GROUP BY nuid
+            ) first_correct USING (nuid)
+            WHERE submission_time <= first_correct_time
+            GROUP BY nuid
+        ) counted GROUP BY attempts ORDER BY attempts"#
+    )
+    .fetch_all(pool.get_ref())
+    .await?
+    .into_iter()
+    .map(|record| AttemptsToFirstCorrect {
+        attempts: record.attempts,
+        applicants: record.applicants,
+    })
+    .collect();
+
+    let leaderboard = select_leaderboard(&pool, scoring, leaderboard_size).await?;
+
+    let rate = |count: i64, total: i64| {
+        if total == 0 {
+            0.0
+        } else {
+            count as f64 / total as f64
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(StatsResponseData {
+        registrations: totals.registrations,
+        submitted: totals.submitted,
+        correct: totals.correct,
+        submission_rate: rate(totals.submitted, totals.registrations),
+        correctness_rate: rate(totals.correct, totals.submitted),
+        median_time_to_completion_seconds: times.median,
+        p90_time_to_completion_seconds: times.p90,
+        registrations_over_time,
+        attempts_to_first_correct,
+        scoring,
+        leaderboard,
+    }))
+}
+
+#[tracing::instrument(
+    name = "Fetching the leaderboard from the database.",
+    skip(pool, scoring, size)
+)]
+pub async fn select_leaderboard(
+    pool: &PgPool,
+    scoring: ScoringPolicy,
+    size: u32,
+) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
+    // Each applicant is represented by the submission the policy reports,
+    // picked by `reported_submission` as in `select_applicants`. Those are
+    // then ranked against each other: correct first, then by score (unscored
+    // submissions count as their correctness), then fastest.
+    let records = query!(
+        r#"SELECT RANK() OVER (
+            ORDER BY correct DESC, COALESCE(score, correct::int) DESC,
+            submission_time - registration_time
+        ) AS "rank!", a.nuid, a.applicant_name, reported.correct AS "correct!", reported.score,
+        EXTRACT(EPOCH FROM submission_time - registration_time)::float8
+            AS "time_to_completion_seconds!"
+        FROM applicants a CROSS JOIN LATERAL reported_submission(a.nuid, $1) reported
+        ORDER BY 1, a.nuid
+        LIMIT $2"#,
+        scoring.to_string(),
+        i64::from(size),
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| LeaderboardEntry {
+            rank: record.rank,
+            nuid: record.nuid,
+            name: record.applicant_name,
+            correct: record.correct,
+            score: record.score,
+            time_to_completion_seconds: record.time_to_completion_seconds,
+        })
+        .collect())
+}
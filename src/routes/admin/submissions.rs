@@ -0,0 +1,116 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{query, PgPool};
+
+use crate::authentication::AdminUser;
+use crate::domain::Nuid;
+use crate::errors::ApiError;
+use crate::routes::applicants::check_if_applicant_exists;
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct SubmissionAttempt {
+    pub submission_time: DateTime<Utc>,
+    pub correct: bool,
+    /// Absent for submissions graded before per-case grading.
+    pub cases_passed: Option<i32>,
+    pub cases_total: Option<i32>,
+    pub score: Option<f64>,
+    pub failed_cases: Option<Vec<i32>>,
+    /// Made after the deadline, but inside the grace period.
+    pub late: bool,
+    /// Absent for submissions made before answers were recorded.
+    pub answer: Option<Vec<String>>,
+    /// The known bug that best explains an incorrect attempt, if any.
+    pub misconception: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct SubmissionHistoryResponseData {
+    pub nuid: String,
+    /// Oldest first.
+    pub submissions: Vec<SubmissionAttempt>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/applicants/{nuid}/submissions",
+    params(("nuid" = String, Path, description = "NUID of the applicant.")),
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "Every submission of the applicant, oldest first.", body = SubmissionHistoryResponseData),
+        (status = 400, description = "Invalid NUID.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+        (status = 404, description = "No applicant registered with the NUID.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Fetching applicant submission history.",
+    skip(_admin, nuid, pool),
+    fields(
+        applicant_nuid = %nuid
+    )
+)]
+pub async fn submission_history(
+    _admin: AdminUser,
+    nuid: web::Path<String>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, ApiError> {
+    let nuid = match Nuid::parse(&nuid) {
+        Ok(nuid) => nuid,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+
+    if !check_if_applicant_exists(&pool, &nuid).await? {
+        tracing::error!(
+            "Record associated with given NUID not found! NUID: {}",
+            nuid
+        );
+        return Err(ApiError::NotFound(format!(
+            "Record associated with given NUID not found! NUID: {}",
+            nuid
+        )));
+    }
+
+    let submissions = select_submissions(&pool, &nuid).await?;
+
+    Ok(HttpResponse::Ok().json(SubmissionHistoryResponseData {
+        nuid: nuid.to_string(),
+        submissions,
+    }))
+}
+
+#[tracing::instrument(
+    name = "Fetching applicant submissions from the database.",
+    skip(pool, nuid)
+)]
+pub async fn select_submissions(
+    pool: &PgPool,
+    nuid: &Nuid,
+) -> Result<Vec<SubmissionAttempt>, sqlx::Error> {
+    let records = query!(
+        r#"SELECT submission_time, correct, cases_passed, cases_total, score, failed_cases, answer,
+        misconception, late FROM submissions WHERE nuid=$1 ORDER BY submission_time, submission_id"#,
+        nuid.as_ref()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| SubmissionAttempt {
+            submission_time: record.submission_time,
+            correct: record.correct,
+            cases_passed: record.cases_passed,
+            cases_total: record.cases_total,
+            score: record.score,
+            failed_cases: record.failed_cases,
+            answer: record.answer,
+            misconception: record.misconception,
+            late: record.late,
+        })
+        .collect())
+}
@@ -0,0 +1,111 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{query, PgPool};
+
+use crate::authentication::{AdminUser, HmacSecret};
+use crate::domain::Nuid;
+use crate::errors::ApiError;
+use crate::routes::rotate_token::{replace_token, RevokedBy};
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct RevokeTokenResponseData {
+    pub nuid: String,
+    pub revoked_at: DateTime<Utc>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/applicants/{nuid}/rotate_token",
+    params(("nuid" = String, Path, description = "NUID of the applicant.")),
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "The applicant's token was revoked and replaced. The applicant recovers the new one through the forgot-token endpoint.", body = RevokeTokenResponseData),
+        (status = 400, description = "Invalid NUID.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+        (status = 404, description = "No applicant registered with the NUID.", body = ErrorResponseData),
+        (status = 409, description = "No email on file, so the applicant could not recover a new token.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Revoking applicant token.",
+    skip(admin, nuid, pool, hmac_secret),
+    fields(
+        applicant_nuid = %nuid
+    )
+)]
+pub async fn rotate_token(
+    admin: AdminUser,
+    nuid: web::Path<String>,
+    pool: web::Data<PgPool>,
+    hmac_secret: web::Data<HmacSecret>,
+) -> Result<HttpResponse, ApiError> {
+    let nuid = match Nuid::parse(&nuid) {
+        Ok(nuid) => nuid,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("Failed to begin transaction: {:?}", e);
+            return Err(ApiError::Unexpected(e.to_string()));
+        }
+    };
+
+    let record = match query!(
+        r#"SELECT token_hash AS "token_hash!", email FROM applicants WHERE nuid=$1 FOR UPDATE"#,
+        nuid.as_ref()
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    {
+        Ok(record) => record,
+        Err(sqlx::Error::RowNotFound) => {
+            tracing::error!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            );
+            return Err(ApiError::NotFound(format!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // The new token is only ever delivered through forgot-token, which needs an
+    // email on file. Applicants registered before emails were collected would
+    // be locked out.
+    if record.email.is_none() {
+        tracing::error!("No email on file for NUID: {}", nuid);
+        return Err(ApiError::Conflict(format!(
+            "No email on file for NUID {}, so the applicant could not recover a new token!",
+            nuid
+        )));
+    }
+
+    let replaced = replace_token(
+        &mut transaction,
+        nuid.as_ref(),
+        &record.token_hash,
+        RevokedBy::Admin {
+            key_hash: &admin.key_hash,
+        },
+        &hmac_secret,
+    )
+    .await?;
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("Failed to commit transaction: {:?}", e);
+        return Err(ApiError::Unexpected(e.to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(RevokeTokenResponseData {
+        nuid: nuid.to_string(),
+        revoked_at: replaced.revoked_at,
+    }))
+}
@@ -0,0 +1,135 @@
+use actix_web::{web, HttpResponse};
+use sqlx::{query, PgPool};
+
+use crate::authentication::AdminUser;
+use crate::domain::{trace_barcode, ChallengeKindName, Nuid, TraceStep};
+use crate::errors::ApiError;
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct CaseTraceResponseData {
+    pub nuid: String,
+    /// Index of the case in the challenge, as in `failed_cases`.
+    pub case: usize,
+    pub barcode: String,
+    pub expected: String,
+    pub steps: Vec<TraceStep>,
+}
+
+pub struct StoredChallenge {
+    pub challenge_kind: String,
+    pub challenge: Vec<String>,
+    pub solution: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/applicants/{nuid}/cases/{case}/trace",
+    params(
+        ("nuid" = String, Path, description = "NUID of the applicant."),
+        ("case" = usize, Path, description = "Index of the case in the applicant's challenge."),
+    ),
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "How the case is decoded, one symbol at a time.", body = CaseTraceResponseData),
+        (status = 400, description = "Invalid NUID or case index.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+        (status = 404, description = "No applicant registered with the NUID, or no such case.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Tracing applicant case.",
+    skip(_admin, path, pool),
+    fields(
+        applicant_nuid = %path.0,
+        case = %path.1
+    )
+)]
+pub async fn trace_case(
+    _admin: AdminUser,
+    path: web::Path<(String, String)>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, ApiError> {
+    let (nuid, case) = path.into_inner();
+    let nuid = match Nuid::parse(&nuid) {
+        Ok(nuid) => nuid,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+    let case = match case.parse::<usize>() {
+        Ok(case) => case,
+        Err(_) => {
+            tracing::error!("Invalid case index! Given: {}", case);
+            return Err(ApiError::Validation(format!(
+                "Invalid case index! Given: {}",
+                case
+            )));
+        }
+    };
+
+    let stored = match retrieve_challenge(&pool, &nuid).await? {
+        Some(stored) => stored,
+        None => {
+            tracing::error!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            );
+            return Err(ApiError::NotFound(format!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            )));
+        }
+    };
+
+    let (barcode, expected) = match (stored.challenge.get(case), stored.solution.get(case)) {
+        (Some(barcode), Some(expected)) => (barcode.clone(), expected.clone()),
+        _ => {
+            tracing::error!("No case {} in the challenge of NUID: {}", case, nuid);
+            return Err(ApiError::NotFound(format!(
+                "Challenge has no case {}! Cases: {}",
+                case,
+                stored.challenge.len()
+            )));
+        }
+    };
+
+    let steps = match ChallengeKindName::parse(&stored.challenge_kind) {
+        Ok(ChallengeKindName::Barcode) => trace_barcode(&barcode),
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Unexpected(err));
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(CaseTraceResponseData {
+        nuid: nuid.to_string(),
+        case,
+        barcode,
+        expected,
+        steps,
+    }))
+}
+
+#[tracing::instrument(
+    name = "Fetching applicant challenge from the database.",
+    skip(pool, nuid)
+)]
+pub async fn retrieve_challenge(
+    pool: &PgPool,
+    nuid: &Nuid,
+) -> Result<Option<StoredChallenge>, sqlx::Error> {
+    let record = query!(
+        r#"SELECT challenge_kind, challenge, solution FROM applicants WHERE nuid=$1"#,
+        nuid.as_ref()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record.map(|record| StoredChallenge {
+        challenge_kind: record.challenge_kind,
+        challenge: record.challenge,
+        solution: record.solution,
+    }))
+}
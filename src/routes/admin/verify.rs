@@ -0,0 +1,148 @@
+use actix_web::{web, HttpResponse};
+use sqlx::{query, PgPool};
+
+use crate::authentication::AdminUser;
+use crate::domain::{ChallengeKindName, Nuid};
+use crate::errors::ApiError;
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct VerifyChallengeResponseData {
+    pub nuid: String,
+    pub generator_version: i32,
+    /// Whether regenerating from the stored seed reproduces the stored challenge and solution.
+    pub matches: bool,
+    /// Indices of the cases whose barcode or solution differ from the regenerated ones.
+    pub mismatched_cases: Vec<usize>,
+}
+
+pub struct SeededChallenge {
+    pub challenge_kind: String,
+    pub seed: Option<i64>,
+    pub generator_version: Option<i32>,
+    pub challenge_parameters: serde_json::Value,
+    pub challenge: Vec<String>,
+    pub solution: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/applicants/{nuid}/challenge/verify",
+    params(
+        ("nuid" = String, Path, description = "NUID of the applicant."),
+    ),
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "How the stored challenge compares with one regenerated from its seed.", body = VerifyChallengeResponseData),
+        (status = 400, description = "Invalid NUID.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+        (status = 404, description = "No applicant registered with the NUID.", body = ErrorResponseData),
+        (status = 409, description = "The challenge was not generated from a seed, or by a generator version that is no longer available.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Verifying applicant challenge.",
+    skip(_admin, nuid, pool),
+    fields(
+        applicant_nuid = %nuid
+    )
+)]
+pub async fn verify_challenge(
+    _admin: AdminUser,
+    nuid: web::Path<String>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, ApiError> {
+    let nuid = match Nuid::parse(&nuid) {
+        Ok(nuid) => nuid,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+
+    let stored = match retrieve_seeded_challenge(&pool, &nuid).await? {
+        Some(stored) => stored,
+        None => {
+            tracing::error!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            );
+            return Err(ApiError::NotFound(format!(
+                "Record associated with given NUID not found! NUID: {}",
+                nuid
+            )));
+        }
+    };
+
+    let (seed, generator_version) = match (stored.seed, stored.generator_version) {
+        (Some(seed), Some(generator_version)) => (seed, generator_version),
+        _ => {
+            tracing::error!("Challenge of NUID {} was not generated from a seed.", nuid);
+            return Err(ApiError::Conflict(format!(
+                "Challenge was not generated from a seed! NUID: {}",
+                nuid
+            )));
+        }
+    };
+
+    let challenge_kind = match ChallengeKindName::parse(&stored.challenge_kind) {
+        Ok(challenge_kind) => challenge_kind,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Unexpected(err));
+        }
+    };
+
+    let regenerated = match challenge_kind.regenerate_challenge(
+        seed as u64,
+        generator_version,
+        &stored.challenge_parameters,
+    ) {
+        Ok(regenerated) => regenerated,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Conflict(err));
+        }
+    };
+
+    let cases = stored.challenge.len().max(regenerated.challenge.len());
+    let mismatched_cases: Vec<usize> = (0..cases)
+        .filter(|&case| {
+            stored.challenge.get(case) != regenerated.challenge.get(case)
+                || stored.solution.get(case) != regenerated.solution.get(case)
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(VerifyChallengeResponseData {
+        nuid: nuid.to_string(),
+        generator_version,
+        matches: mismatched_cases.is_empty(),
+        mismatched_cases,
+    }))
+}
+
+#[tracing::instrument(
+    name = "Fetching applicant challenge and seed from the database.",
+    skip(pool, nuid)
+)]
+pub async fn retrieve_seeded_challenge(
+    pool: &PgPool,
+    nuid: &Nuid,
+) -> Result<Option<SeededChallenge>, sqlx::Error> {
+    let record = query!(
+        r#"SELECT challenge_kind, seed, generator_version, challenge_parameters, challenge, solution
+        FROM applicants WHERE nuid=$1"#,
+        nuid.as_ref()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record.map(|record| SeededChallenge {
+        challenge_kind: record.challenge_kind,
+        seed: record.seed,
+        generator_version: record.generator_version,
+        challenge_parameters: record.challenge_parameters,
+        challenge: record.challenge,
+        solution: record.solution,
+    }))
+}
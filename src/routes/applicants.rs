@@ -2,11 +2,22 @@ use actix_web::{web, HttpResponse};
 use sqlx::{query, PgPool};
 use std::time::Duration;
 
-use crate::domain::{ApplicantName, Nuid};
+use crate::authentication::AdminUser;
+use crate::configuration::ScoringSettings;
+use crate::domain::{ApplicantName, Nuid, ScoringPolicy};
+use crate::errors::ApiError;
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 pub struct ApplicantsBodyData(Vec<String>);
 
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScoringQuery {
+    /// Which submission to report; the configured default if absent.
+    #[param(inline)]
+    pub scoring: Option<ScoringPolicy>,
+}
+
 impl TryFrom<ApplicantsBodyData> for Nuids {
     type Error = String;
 
@@ -56,43 +67,75 @@ pub struct IntermediateApplicant {
     pub nuid: String,
     pub name: String,
     pub correct: bool,
+    pub score: Option<f64>,
     pub time_to_completion: Duration,
+    pub late: bool,
+    pub misconception: Option<String>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct ReturnedApplicant {
+    #[schema(value_type = String)]
     pub nuid: Nuid,
+    #[schema(value_type = String)]
     pub name: ApplicantName,
     pub correct: bool,
+    pub score: Option<f64>,
+    /// Serialized as `{ "secs": .., "nanos": .. }`.
+    #[schema(value_type = Object)]
     pub time_to_completion: Duration,
+    /// Whether the reported submission was accepted after the deadline, during the grace period.
+    pub late: bool,
+    /// The known bug that best explains an incorrect reported submission, if any.
+    pub misconception: Option<String>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct ApplicantsResponseData {
+    /// The policy that picked each applicant's reported submission.
+    pub scoring: ScoringPolicy,
     pub applicants_found: Vec<ReturnedApplicant>,
     pub applicants_not_submitted: Vec<String>,
     pub applicants_not_found: Vec<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/applicants",
+    params(ScoringQuery),
+    request_body = ApplicantsBodyData,
+    security(("admin_api_key" = [])),
+    responses(
+        (status = 200, description = "Every applicant was found.", body = ApplicantsResponseData),
+        (status = 400, description = "Invalid NUIDs or scoring policy.", body = ErrorResponseData),
+        (status = 401, description = "Missing or unknown admin API key.", body = ErrorResponseData),
+        (status = 403, description = "Revoked admin API key.", body = ErrorResponseData),
+        (status = 404, description = "Some applicants were not found.", body = ApplicantsResponseData),
+    )
+)]
 #[tracing::instrument(
     name = "Fetching applicants.",
-    skip(body, pool),
+    skip(_admin, query, body, pool, scoring_settings),
     fields(
         nuids = %body.0.0.join(", ")
     )
 )]
 pub async fn applicants(
+    _admin: AdminUser,
+    query: web::Query<ScoringQuery>,
     body: web::Json<ApplicantsBodyData>,
     pool: web::Data<PgPool>,
-) -> HttpResponse {
-    let nuids = match body.0.try_into() {
+    scoring_settings: web::Data<ScoringSettings>,
+) -> Result<HttpResponse, ApiError> {
+    let scoring = query.scoring.unwrap_or(scoring_settings.default_policy);
+    let nuids: Nuids = match body.0.try_into() {
         Ok(nuids) => nuids,
         Err(err) => {
             tracing::error!(err);
-            return HttpResponse::BadRequest().body(err);
+            return Err(ApiError::Validation(err));
         }
     };
-    match select_applicants(&pool, &nuids).await {
+    match select_applicants(&pool, &nuids, scoring).await {
         Ok(applicants) => {
             let mut returned_applicants = Vec::with_capacity(applicants.len());
 
@@ -106,7 +149,10 @@ pub async fn applicants(
                             nuid,
                             name,
                             correct: applicant.correct,
+                            score: applicant.score,
                             time_to_completion: applicant.time_to_completion,
+                            late: applicant.late,
+                            misconception: applicant.misconception,
                         });
                     }
                     (Err(_), _) => {
@@ -114,14 +160,20 @@ pub async fn applicants(
                             "Invalid database state for NUID! Given: {}",
                             applicant.nuid
                         );
-                        return HttpResponse::InternalServerError().finish();
+                        return Err(ApiError::Unexpected(format!(
+                            "Invalid database state for NUID! Given: {}",
+                            applicant.nuid
+                        )));
                     }
                     (_, Err(_)) => {
                         tracing::error!(
                             "Invalid database state for name! Given: {}",
                             applicant.name
                         );
-                        return HttpResponse::InternalServerError().finish();
+                        return Err(ApiError::Unexpected(format!(
+                            "Invalid database state for name! Given: {}",
+                            applicant.name
+                        )));
                     }
                 }
             }
@@ -148,48 +200,53 @@ pub async fn applicants(
                         Ok(false) => {
                             applicants_not_found.push(nuid.to_string());
                         }
-                        Err(_) => {
+                        Err(e) => {
                             tracing::error!(
                                 "Error while checking if applicant with NUID of {} exists!",
                                 nuid
                             );
-                            return HttpResponse::InternalServerError().finish();
+                            return Err(e.into());
                         }
                     }
                 }
 
                 if applicants_not_found.is_empty() {
-                    HttpResponse::Ok().json(ApplicantsResponseData {
+                    Ok(HttpResponse::Ok().json(ApplicantsResponseData {
+                        scoring,
                         applicants_found: returned_applicants,
                         applicants_not_submitted,
                         applicants_not_found: Vec::new(),
-                    })
+                    }))
                 } else {
-                    HttpResponse::NotFound().json(ApplicantsResponseData {
+                    // Partial results are still useful, so this stays a data response.
+                    Ok(HttpResponse::NotFound().json(ApplicantsResponseData {
+                        scoring,
                         applicants_found: returned_applicants,
                         applicants_not_submitted,
                         applicants_not_found,
-                    })
+                    }))
                 }
             } else {
-                HttpResponse::Ok().json(ApplicantsResponseData {
+                Ok(HttpResponse::Ok().json(ApplicantsResponseData {
+                    scoring,
                     applicants_found: returned_applicants,
                     applicants_not_submitted: Vec::new(),
                     applicants_not_found: Vec::new(),
-                })
+                }))
             }
         }
         Err(sqlx::Error::RowNotFound) => {
             let applicants_not_found: Vec<String> =
                 nuids.0.iter().map(|nuid| nuid.to_string()).collect();
 
-            HttpResponse::NotFound().json(ApplicantsResponseData {
+            Ok(HttpResponse::NotFound().json(ApplicantsResponseData {
+                scoring,
                 applicants_found: Vec::new(),
                 applicants_not_submitted: Vec::new(),
                 applicants_not_found,
-            })
+            }))
         }
-        Err(_) => HttpResponse::InternalServerError().finish(),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -200,14 +257,18 @@ pub async fn applicants(
 pub async fn select_applicants(
     pool: &PgPool,
     nuids: &Nuids,
+    scoring: ScoringPolicy,
 ) -> Result<Vec<IntermediateApplicant>, sqlx::Error> {
     let nuids: Vec<String> = nuids.0.iter().map(|nuid| nuid.to_string()).collect();
 
     let records = query!(
-        r#"SELECT DISTINCT ON (nuid) nuid, applicant_name, correct, submission_time, 
-        registration_time FROM submissions JOIN applicants using(nuid) where 
-        nuid=ANY($1) ORDER BY nuid, submission_time DESC;"#,
-        &nuids.as_slice()
+        r#"SELECT a.nuid, a.applicant_name, reported.correct AS "correct!", reported.score,
+        reported.submission_time AS "submission_time!", a.registration_time,
+        reported.late AS "late!", reported.misconception
+        FROM applicants a CROSS JOIN LATERAL reported_submission(a.nuid, $2) reported
+        WHERE a.nuid=ANY($1) ORDER BY a.nuid;"#,
+        &nuids.as_slice(),
+        scoring.to_string(),
     )
     .fetch_all(pool)
     .await?;
@@ -225,7 +286,10 @@ pub async fn select_applicants(
                 nuid: record.nuid.clone(),
                 name: record.applicant_name.clone(),
                 correct: record.correct,
+                score: record.score,
                 time_to_completion,
+                late: record.late,
+                misconception: record.misconception.clone(),
             }
         })
         .collect())
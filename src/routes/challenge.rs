@@ -1,63 +1,95 @@
 use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
 use sqlx::{query, PgPool};
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+use crate::authentication::HmacSecret;
+use crate::domain::{ApplicantToken, ChallengeKindName};
+use crate::errors::ApiError;
+use crate::routes::rotate_token::unknown_token;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, utoipa::ToSchema)]
 pub struct ChallengeResponseData {
     pub challenge: Vec<String>,
+    pub prompt: String,
+    pub deadline: Option<DateTime<Utc>>,
 }
 
-#[tracing::instrument(
-    name = "Forgot challenge.",
-    skip(token, pool),
-    fields(
-        applicant_token = %token
+#[utoipa::path(
+    get,
+    path = "/v1/challenge/{token}",
+    params(("token" = String, Path, description = "Token issued at registration.")),
+    responses(
+        (status = 200, description = "The applicant's challenge.", body = ChallengeResponseData),
+        (status = 400, description = "Malformed token.", body = ErrorResponseData),
+        (status = 401, description = "The token has been revoked.", body = ErrorResponseData),
+        (status = 404, description = "No applicant holds the token.", body = ErrorResponseData),
+        (status = 410, description = "The deadline has passed.", body = ErrorResponseData),
     )
 )]
-pub async fn challenge(token: web::Path<String>, pool: web::Data<PgPool>) -> HttpResponse {
-    let token = match uuid::Uuid::parse_str(&token) {
+#[tracing::instrument(name = "Forgot challenge.", skip(token, pool, hmac_secret))]
+pub async fn challenge(
+    token: web::Path<String>,
+    pool: web::Data<PgPool>,
+    hmac_secret: web::Data<HmacSecret>,
+) -> Result<HttpResponse, ApiError> {
+    let token = match ApplicantToken::parse(&token) {
         Ok(token) => token,
-        Err(_) => {
-            tracing::error!("Invalid token! Given: {}", token);
-            return HttpResponse::BadRequest().body(format!("Invalid token! Given: {}", token));
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
         }
     };
-    match retrieve_challenge(&pool, &token).await {
-        Ok(response_data) => HttpResponse::Ok().json(response_data),
+    let token_hash = token.hash(&hmac_secret.0);
+    match retrieve_challenge(&pool, &token_hash).await {
+        Ok(response_data) => match response_data.deadline {
+            Some(deadline) if Utc::now() > deadline => {
+                tracing::error!("Challenge expired at {}", deadline);
+                Err(ApiError::Expired(deadline))
+            }
+            _ => Ok(HttpResponse::Ok().json(response_data)),
+        },
         Err(sqlx::Error::RowNotFound) => {
-            tracing::error!("Row not found: {:?}", token);
-            HttpResponse::NotFound().body(format!(
-                "Record associated with given token not found! Token: {}",
-                token
-            ))
-        }
-        Err(e) => {
-            tracing::error!("Failed to execute query: {:?}", e);
-            HttpResponse::InternalServerError().finish()
+            tracing::error!("No applicant holds the token.");
+            Err(unknown_token(&pool, &token, &token_hash).await)
         }
+        Err(e) => Err(e.into()),
     }
 }
 
 #[tracing::instrument(
     name = "Fetching applicant challenge from the database.",
-    skip(token, pool)
+    skip(token_hash, pool)
 )]
 pub async fn retrieve_challenge(
     pool: &PgPool,
-    token: &uuid::Uuid,
+    token_hash: &str,
 ) -> Result<ChallengeResponseData, sqlx::Error> {
-    let record = query!(r#"SELECT challenge FROM applicants WHERE token=$1"#, token)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to execute query: {:?}", e);
-            e
-        })?;
+    let record = query!(
+        r#"SELECT challenge, challenge_kind, challenge_parameters, deadline FROM applicants
+        WHERE token_hash=$1"#,
+        token_hash
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
 
     if record.challenge.is_empty() {
         return Err(sqlx::Error::RowNotFound);
     }
 
+    let challenge_kind = ChallengeKindName::parse(&record.challenge_kind)
+        .and_then(|name| name.challenge_kind(&record.challenge_parameters))
+        .map_err(|e| {
+            tracing::error!("Invalid database state for challenge kind! {}", e);
+            sqlx::Error::Decode(e.into())
+        })?;
+
     Ok(ChallengeResponseData {
         challenge: record.challenge,
+        prompt: challenge_kind.prompt().to_string(),
+        deadline: record.deadline,
     })
 }
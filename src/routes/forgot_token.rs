@@ -1,68 +1,319 @@
-use crate::domain::Nuid;
+use crate::authentication::HmacSecret;
+use crate::configuration::RecoverySettings;
+use crate::domain::{Nuid, RecoveryCode};
+use crate::errors::ApiError;
+use crate::mailer::{Email, Mailer};
+use crate::rate_limiting::RateLimiter;
+use crate::routes::rotate_token::{replace_token, RevokedBy};
 
-use actix_web::{web, HttpResponse};
-use sqlx::{query, PgPool};
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{query, PgPool, Postgres, Transaction};
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct ForgotTokenResponseData {
+    pub message: String,
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+pub struct VerifyRecoveryCodeBodyData {
+    pub code: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct VerifyRecoveryCodeResponseData {
     pub token: String,
 }
 
+pub struct StoredRecoveryCode {
+    pub code_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub failed_attempts: i32,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/forgot_token/{nuid}",
+    params(("nuid" = String, Path, description = "NUID the applicant registered with.")),
+    responses(
+        (status = 202, description = "A recovery code was emailed, if an email is on file for the NUID.", body = ForgotTokenResponseData),
+        (status = 400, description = "Invalid NUID.", body = ErrorResponseData),
+        (status = 429, description = "Too many requests from the client or for the NUID.", body = ErrorResponseData),
+    )
+)]
 #[tracing::instrument(
     name = "Forgot token.",
-    skip(nuid, pool),
+    skip(req, nuid, pool, rate_limiter, mailer, recovery_settings, hmac_secret),
     fields(
         applicant_nuid = %nuid
     )
 )]
-pub async fn forgot_token(nuid: web::Path<String>, pool: web::Data<PgPool>) -> HttpResponse {
+pub async fn forgot_token(
+    req: HttpRequest,
+    nuid: web::Path<String>,
+    pool: web::Data<PgPool>,
+    rate_limiter: web::Data<RateLimiter>,
+    mailer: web::Data<dyn Mailer>,
+    recovery_settings: web::Data<RecoverySettings>,
+    hmac_secret: web::Data<HmacSecret>,
+) -> Result<HttpResponse, ApiError> {
+    rate_limiter.check_client(&req)?;
     let nuid = match Nuid::parse(&nuid) {
         Ok(nuid) => nuid,
         Err(err) => {
             tracing::error!(err);
-            return HttpResponse::BadRequest().json(err);
+            return Err(ApiError::Validation(err));
         }
     };
-    match retrieve_token(&pool, &nuid).await {
-        Ok(response_data) => HttpResponse::Ok().body(response_data.token),
-        Err(sqlx::Error::RowNotFound) => {
-            tracing::error!(
-                "Record associated with given NUID not found! NUID: {}",
-                nuid
-            );
-            HttpResponse::NotFound().body(format!(
-                "Record associated with given NUID not found! NUID: {}",
-                nuid
-            ))
+    rate_limiter.check_nuid(&nuid)?;
+
+    // The response is the same whether or not the NUID is known, so it cannot
+    // be used to find out who has registered.
+    let response = HttpResponse::Accepted().json(ForgotTokenResponseData {
+        message: "If an email is on file for this NUID, a recovery code has been sent to it."
+            .to_string(),
+    });
+
+    let email = match retrieve_email(&pool, &nuid).await? {
+        Some(email) => email,
+        None => {
+            tracing::error!("No email on file for NUID: {}", nuid);
+            return Ok(response);
         }
+    };
+
+    let code = RecoveryCode::generate(&mut rand::thread_rng());
+    let expires_at = Utc::now() + recovery_settings.code_ttl();
+    store_recovery_code(&pool, &nuid, &code, expires_at, &hmac_secret).await?;
+
+    if let Err(e) = mailer
+        .send(Email {
+            to: email,
+            subject: "Your token recovery code".to_string(),
+            body: format!(
+                "Your recovery code is {}. It expires at {}.\n\nIf you did not ask to recover your token, ignore this email.",
+                code, expires_at
+            ),
+        })
+        .await
+    {
+        tracing::error!("Failed to send recovery code: {}", e);
+        return Err(ApiError::Unexpected(e));
+    }
+
+    Ok(response)
+}
+
+/// `GET /forgot_token/{nuid}` used to return the token outright. It is kept
+/// on the unversioned scope only to point old scripts at the emailed-code flow.
+#[tracing::instrument(name = "Retired forgot token.", skip(nuid), fields(applicant_nuid = %nuid))]
+pub async fn forgot_token_retired(nuid: web::Path<String>) -> Result<HttpResponse, ApiError> {
+    Err(ApiError::Gone(format!(
+        "Tokens are no longer returned here! POST /v1/forgot_token/{} to have a recovery code emailed, then POST it to /v1/forgot_token/{}/verify.",
+        nuid, nuid
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/forgot_token/{nuid}/verify",
+    params(("nuid" = String, Path, description = "NUID the applicant registered with.")),
+    request_body = VerifyRecoveryCodeBodyData,
+    responses(
+        (status = 200, description = "A new token for the applicant; the old one is revoked.", body = VerifyRecoveryCodeResponseData),
+        (status = 400, description = "Invalid NUID or code.", body = ErrorResponseData),
+        (status = 403, description = "Wrong, expired or already used code.", body = ErrorResponseData),
+        (status = 429, description = "Too many requests from the client or for the NUID.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(
+    name = "Verify recovery code.",
+    skip(req, nuid, body, pool, rate_limiter, recovery_settings, hmac_secret),
+    fields(
+        applicant_nuid = %nuid
+    )
+)]
+pub async fn verify_recovery_code(
+    req: HttpRequest,
+    nuid: web::Path<String>,
+    body: web::Json<VerifyRecoveryCodeBodyData>,
+    pool: web::Data<PgPool>,
+    rate_limiter: web::Data<RateLimiter>,
+    recovery_settings: web::Data<RecoverySettings>,
+    hmac_secret: web::Data<HmacSecret>,
+) -> Result<HttpResponse, ApiError> {
+    rate_limiter.check_client(&req)?;
+    let nuid = match Nuid::parse(&nuid) {
+        Ok(nuid) => nuid,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+    let code = match RecoveryCode::parse(&body.code) {
+        Ok(code) => code,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+    rate_limiter.check_nuid(&nuid)?;
+
+    let rejected = || ApiError::Forbidden("Invalid or expired recovery code!".to_string());
+
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
         Err(e) => {
-            tracing::error!("Failed to execute query: {:?}", e);
-            HttpResponse::InternalServerError().finish()
+            tracing::error!("Failed to begin transaction: {:?}", e);
+            return Err(ApiError::Unexpected(e.to_string()));
+        }
+    };
+
+    let stored = match retrieve_recovery_code(&mut transaction, &nuid).await? {
+        Some(stored) => stored,
+        None => {
+            tracing::error!("No recovery code issued for NUID: {}", nuid);
+            return Err(rejected());
         }
+    };
+
+    if Utc::now() > stored.expires_at {
+        tracing::error!("Recovery code for NUID {} has expired", nuid);
+        delete_recovery_code(&mut transaction, &nuid).await?;
+        commit(transaction).await?;
+        return Err(rejected());
+    }
+
+    // The row is kept so that requesting another code does not restore the
+    // guess budget.
+    if stored.failed_attempts as u32 >= recovery_settings.max_code_attempts {
+        tracing::error!("Too many wrong recovery codes for NUID: {}", nuid);
+        return Err(rejected());
+    }
+
+    if code.hash(&nuid, &hmac_secret.0) != stored.code_hash {
+        tracing::error!("Wrong recovery code for NUID: {}", nuid);
+        record_failed_attempt(&mut transaction, &nuid).await?;
+        commit(transaction).await?;
+        return Err(rejected());
     }
+
+    // Codes are single use.
+    delete_recovery_code(&mut transaction, &nuid).await?;
+    // Only the hash of the old token is stored, so recovering issues a new one.
+    let old_token_hash = query!(
+        r#"SELECT token_hash AS "token_hash!" FROM applicants WHERE nuid=$1 FOR UPDATE"#,
+        nuid.as_ref()
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .token_hash;
+    let replaced = replace_token(
+        &mut transaction,
+        nuid.as_ref(),
+        &old_token_hash,
+        RevokedBy::Recovery,
+        &hmac_secret,
+    )
+    .await?;
+    commit(transaction).await?;
+
+    Ok(HttpResponse::Ok().json(VerifyRecoveryCodeResponseData {
+        token: replaced.token.to_string(),
+    }))
 }
 
-#[tracing::instrument(name = "Fetching applicant token from the database.", skip(nuid, pool))]
-pub async fn retrieve_token(
+async fn commit(transaction: Transaction<'_, Postgres>) -> Result<(), ApiError> {
+    transaction.commit().await.map_err(|e| {
+        tracing::error!("Failed to commit transaction: {:?}", e);
+        ApiError::Unexpected(e.to_string())
+    })
+}
+
+#[tracing::instrument(name = "Fetching applicant email from the database.", skip(nuid, pool))]
+pub async fn retrieve_email(pool: &PgPool, nuid: &Nuid) -> Result<Option<String>, sqlx::Error> {
+    let record = query!(
+        r#"SELECT email FROM applicants WHERE nuid=$1"#,
+        nuid.as_ref()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record.and_then(|record| record.email))
+}
+
+#[tracing::instrument(
+    name = "Saving recovery code in the database.",
+    skip(pool, nuid, code, expires_at, hmac_secret)
+)]
+pub async fn store_recovery_code(
     pool: &PgPool,
     nuid: &Nuid,
-) -> Result<ForgotTokenResponseData, sqlx::Error> {
+    code: &RecoveryCode,
+    expires_at: DateTime<Utc>,
+    hmac_secret: &HmacSecret,
+) -> Result<(), sqlx::Error> {
+    // Requesting a new code replaces any earlier one, but wrong guesses against
+    // a code that is still live carry over, so asking again does not buy more.
+    query!(
+        r#"INSERT INTO recovery_codes (nuid, code_hash, expires_at) VALUES ($1, $2, $3)
+        ON CONFLICT (nuid) DO UPDATE SET code_hash = $2, expires_at = $3,
+        failed_attempts = CASE WHEN recovery_codes.expires_at < now() THEN 0
+            ELSE recovery_codes.failed_attempts END;"#,
+        nuid.as_ref(),
+        code.hash(nuid, &hmac_secret.0),
+        expires_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "Fetching recovery code from the database.",
+    skip(transaction, nuid)
+)]
+pub async fn retrieve_recovery_code(
+    transaction: &mut Transaction<'_, Postgres>,
+    nuid: &Nuid,
+) -> Result<Option<StoredRecoveryCode>, sqlx::Error> {
     let record = query!(
-        r#"SELECT token FROM applicants WHERE nuid=$1"#,
+        r#"SELECT code_hash, expires_at, failed_attempts FROM recovery_codes
+        WHERE nuid=$1 FOR UPDATE"#,
         nuid.as_ref()
     )
-    .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Failed to execute query: {:?}", e);
-        e
-    })?;
-
-    if record.token.to_string().is_empty() {
-        return Err(sqlx::Error::RowNotFound);
-    }
+    .fetch_optional(&mut **transaction)
+    .await?;
 
-    Ok(ForgotTokenResponseData {
-        token: record.token.to_string(),
-    })
+    Ok(record.map(|record| StoredRecoveryCode {
+        code_hash: record.code_hash,
+        expires_at: record.expires_at,
+        failed_attempts: record.failed_attempts,
+    }))
+}
+
+pub async fn record_failed_attempt(
+    transaction: &mut Transaction<'_, Postgres>,
+    nuid: &Nuid,
+) -> Result<(), sqlx::Error> {
+    query!(
+        r#"UPDATE recovery_codes SET failed_attempts = failed_attempts + 1 WHERE nuid=$1"#,
+        nuid.as_ref()
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_recovery_code(
+    transaction: &mut Transaction<'_, Postgres>,
+    nuid: &Nuid,
+) -> Result<(), sqlx::Error> {
+    query!(r#"DELETE FROM recovery_codes WHERE nuid=$1"#, nuid.as_ref())
+        .execute(&mut **transaction)
+        .await?;
+
+    Ok(())
 }
@@ -1,5 +1,10 @@
 use actix_web::HttpResponse;
 
+#[utoipa::path(
+    get,
+    path = "/health_check",
+    responses((status = 200, description = "The server is up."))
+)]
 pub async fn health_check() -> HttpResponse {
     HttpResponse::Ok().finish()
 }
@@ -1,13 +1,18 @@
+pub mod admin;
 pub mod applicants;
 pub mod challenge;
 mod forgot_token;
 mod health_check;
+mod openapi;
 pub mod register;
+pub mod rotate_token;
 pub mod submit;
 
 pub use applicants::{applicants, ApplicantsBodyData};
 pub use challenge::{challenge, ChallengeResponseData};
-pub use forgot_token::forgot_token;
+pub use forgot_token::{forgot_token, forgot_token_retired, verify_recovery_code};
 pub use health_check::health_check;
+pub use openapi::{openapi, ApiDoc};
 pub use register::{register, RegisterResponseData};
+pub use rotate_token::rotate_token;
 pub use submit::{submit, SubmitResponseData};
@@ -0,0 +1,82 @@
+use actix_web::HttpResponse;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::domain::{Difficulty, ScoringPolicy, TraceStep};
+use crate::errors::ErrorResponseData;
+use crate::routes::{
+    admin, applicants, challenge, forgot_token, health_check, register, rotate_token, submit,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Generate Coding Challenge Server"),
+    paths(
+        health_check::health_check,
+        register::register,
+        forgot_token::forgot_token,
+        forgot_token::verify_recovery_code,
+        challenge::challenge,
+        submit::submit,
+        applicants::applicants,
+        rotate_token::rotate_token,
+        admin::applicants::list_applicants,
+        admin::export::export_applicants,
+        admin::deadline::extend_deadline,
+        admin::token::rotate_token,
+        admin::submissions::submission_history,
+        admin::stats::stats,
+        admin::trace::trace_case,
+        admin::verify::verify_challenge,
+    ),
+    components(schemas(
+        ErrorResponseData,
+        register::BodyData,
+        register::RegisterResponseData,
+        forgot_token::ForgotTokenResponseData,
+        forgot_token::VerifyRecoveryCodeBodyData,
+        forgot_token::VerifyRecoveryCodeResponseData,
+        challenge::ChallengeResponseData,
+        submit::BodyData,
+        submit::SubmitResponseData,
+        applicants::ApplicantsBodyData,
+        applicants::ApplicantsResponseData,
+        applicants::ReturnedApplicant,
+        ScoringPolicy,
+        Difficulty,
+        admin::applicants::ListApplicantsResponseData,
+        admin::applicants::ListedApplicant,
+        admin::deadline::ExtendDeadlineBodyData,
+        rotate_token::RotateTokenResponseData,
+        admin::deadline::ExtendDeadlineResponseData,
+        admin::token::RevokeTokenResponseData,
+        admin::submissions::SubmissionAttempt,
+        admin::submissions::SubmissionHistoryResponseData,
+        admin::stats::StatsResponseData,
+        admin::stats::RegistrationPeriod,
+        admin::stats::AttemptsToFirstCorrect,
+        admin::stats::LeaderboardEntry,
+        admin::trace::CaseTraceResponseData,
+        admin::verify::VerifyChallengeResponseData,
+        TraceStep,
+    )),
+    modifiers(&AdminApiKey)
+)]
+pub struct ApiDoc;
+
+struct AdminApiKey;
+
+impl Modify for AdminApiKey {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "admin_api_key",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+pub async fn openapi() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
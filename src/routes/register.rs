@@ -1,16 +1,27 @@
 use std::time::SystemTime;
 
-use crate::domain::{generate_challenge, ApplicantName, Nuid, RegisterApplicant};
+use crate::authentication::HmacSecret;
+use crate::configuration::{ChallengeSettings, RegistrationSettings};
+use crate::domain::{
+    ApplicantEmail, ApplicantName, ApplicantToken, ChallengeKind, ChallengeKindName, Difficulty,
+    Nuid, RegisterApplicant,
+};
+use crate::errors::ApiError;
+use crate::rate_limiting::RateLimiter;
 
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use chrono::{DateTime, Utc};
 use sqlx::{query, PgPool};
-use uuid::Uuid;
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+#[schema(as = RegisterBodyData)]
 pub struct BodyData {
     pub name: String,
     pub nuid: String,
+    pub email: String,
+    /// One of the difficulties the server lets applicants choose; the
+    /// configured default when absent.
+    pub difficulty: Option<String>,
 }
 
 impl TryFrom<BodyData> for RegisterApplicant {
@@ -19,80 +30,163 @@ impl TryFrom<BodyData> for RegisterApplicant {
     fn try_from(body: BodyData) -> Result<Self, Self::Error> {
         let name = ApplicantName::parse(&body.name)?;
         let nuid = Nuid::parse(&body.nuid)?;
-        Ok(RegisterApplicant { name, nuid })
+        let email = ApplicantEmail::parse(&body.email)?;
+        let difficulty = body
+            .difficulty
+            .as_deref()
+            .map(Difficulty::parse)
+            .transpose()?;
+        Ok(RegisterApplicant {
+            name,
+            nuid,
+            email,
+            difficulty,
+        })
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct RegisterResponseData {
     pub token: String,
     pub challenge: Vec<String>,
+    pub prompt: String,
+    pub deadline: DateTime<Utc>,
+    pub difficulty: Difficulty,
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/register",
+    request_body = RegisterBodyData,
+    responses(
+        (status = 200, description = "Applicant registered and issued a challenge.", body = RegisterResponseData),
+        (status = 400, description = "Invalid name, NUID, email or difficulty, an email outside the allowed domains, or a difficulty applicants may not choose.", body = ErrorResponseData),
+        (status = 409, description = "NUID has already registered.", body = ErrorResponseData),
+        (status = 429, description = "Too many requests from the client or for the NUID.", body = ErrorResponseData),
+    )
+)]
 #[tracing::instrument(
     name = "Adding a new applicant.",
-    skip(body, pool),
+    skip(req, body, pool, challenge_settings, registration_settings, rate_limiter, hmac_secret),
     fields(
-        applicant_name = %body.name,
-        applicant_nuid = %body.nuid
+        applicant_name = tracing::field::Empty,
+        applicant_nuid = tracing::field::Empty
     )
 )]
-pub async fn register(body: web::Json<BodyData>, pool: web::Data<PgPool>) -> HttpResponse {
-    let register_applicant = match body.0.try_into() {
+pub async fn register(
+    req: HttpRequest,
+    body: Result<web::Json<BodyData>, actix_web::Error>,
+    pool: web::Data<PgPool>,
+    challenge_settings: web::Data<ChallengeSettings>,
+    registration_settings: web::Data<RegistrationSettings>,
+    rate_limiter: web::Data<RateLimiter>,
+    hmac_secret: web::Data<HmacSecret>,
+) -> Result<HttpResponse, ApiError> {
+    // Charged first, so requests failing validation cannot probe without limit.
+    rate_limiter.check_client(&req)?;
+    // Malformed bodies were already logged by the JSON error handler.
+    let body = body.map_err(|e| ApiError::Validation(e.to_string()))?;
+    let span = tracing::Span::current();
+    span.record("applicant_name", body.name.as_str());
+    span.record("applicant_nuid", body.nuid.as_str());
+
+    let register_applicant: RegisterApplicant = match body.into_inner().try_into() {
         Ok(register_applicant) => register_applicant,
         Err(err) => {
             tracing::error!(err);
-            return HttpResponse::BadRequest().json(err);
+            return Err(ApiError::Validation(err));
         }
     };
-    match insert_applicant(&pool, &register_applicant).await {
-        Ok(response_data) => HttpResponse::Ok().json(response_data),
-        Err(e) => {
-            tracing::error!("Failed to execute query: {:?}", e);
-            match e {
-                sqlx::Error::Database(db_err) => {
-                    if db_err.code() == Some(std::borrow::Cow::Borrowed("23505")) {
-                        HttpResponse::Conflict().body(format!("NUID {} has already registered! Use the forgot-token endpoint to retrieve your token.", register_applicant.nuid.as_ref()))
-                    } else {
-                        HttpResponse::InternalServerError()
-                            .body(format!("Database error: {:?}", db_err))
-                    }
-                }
-                _ => HttpResponse::InternalServerError().body(format!("SQLX error: {:?}", e)),
-            }
+    if !register_applicant
+        .email
+        .is_in_domains(&registration_settings.allowed_email_domains)
+    {
+        tracing::error!(
+            "Email domain not allowed! Given: {}",
+            register_applicant.email.domain()
+        );
+        return Err(ApiError::Validation(format!(
+            "Email domain not allowed! Given: {}, allowed: {}",
+            register_applicant.email.domain(),
+            registration_settings.allowed_email_domains.join(", ")
+        )));
+    }
+    rate_limiter.check_nuid(&register_applicant.nuid)?;
+    let difficulty = match challenge_settings.difficulty(register_applicant.difficulty) {
+        Ok(difficulty) => difficulty,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+    let challenge_kind = match challenge_settings.challenge_kind(difficulty) {
+        Ok(challenge_kind) => challenge_kind,
+        Err(err) => {
+            tracing::error!("Invalid challenge configuration: {}", err);
+            return Err(ApiError::Unexpected(err));
+        }
+    };
+    match insert_applicant(
+        &pool,
+        &register_applicant,
+        challenge_settings.kind,
+        challenge_kind.as_ref(),
+        difficulty,
+        challenge_settings.time_limit(),
+        &hmac_secret,
+    )
+    .await
+    {
+        Ok(response_data) => Ok(HttpResponse::Ok().json(response_data)),
+        Err(sqlx::Error::Database(db_err))
+            if db_err.code() == Some(std::borrow::Cow::Borrowed("23505")) =>
+        {
+            tracing::error!("Failed to execute query: {:?}", db_err);
+            Err(ApiError::Conflict(format!(
+                "NUID {} has already registered! Use the forgot-token endpoint to retrieve your token.",
+                register_applicant.nuid.as_ref()
+            )))
         }
+        Err(e) => Err(e.into()),
     }
 }
 
 #[tracing::instrument(
     name = "Saving new applicant details in the database.",
-    skip(register_applicant, pool)
+    skip(register_applicant, pool, challenge_kind, hmac_secret)
 )]
 pub async fn insert_applicant(
     pool: &PgPool,
     register_applicant: &RegisterApplicant,
+    challenge_kind_name: ChallengeKindName,
+    challenge_kind: &dyn ChallengeKind,
+    difficulty: Difficulty,
+    time_limit: chrono::Duration,
+    hmac_secret: &HmacSecret,
 ) -> Result<RegisterResponseData, sqlx::Error> {
     let registration_time: DateTime<Utc> = SystemTime::now().into();
-    let token = Uuid::new_v4();
-    let challenge = generate_challenge(
-        256,
-        vec![
-            String::from(""),
-            String::from("#12#34!#59^#67%#"),
-            String::from("#12^!%%###34^#"),
-            String::from("##"),
-        ],
-    );
+    let deadline = registration_time + time_limit;
+    let token = ApplicantToken::generate();
+    let seed: u64 = rand::random();
+    let challenge = challenge_kind.generate_challenge(seed);
 
     query!(
-        r#"INSERT INTO applicants (nuid, applicant_name, registration_time, token, challenge, solution)
-        VALUES ($1, $2, $3, $4, $5, $6);"#,
+        r#"INSERT INTO applicants (nuid, applicant_name, registration_time, token_hash, challenge, solution, challenge_kind,
+        seed, generator_version, challenge_parameters, deadline, email, difficulty)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13);"#,
         register_applicant.nuid.as_ref(),
         register_applicant.name.as_ref(),
         registration_time,
-        &token,
+        token.hash(&hmac_secret.0),
         &challenge.challenge,
         &challenge.solution,
+        challenge_kind_name.to_string(),
+        seed as i64,
+        challenge_kind.version(),
+        challenge_kind.parameters(),
+        deadline,
+        register_applicant.email.as_ref(),
+        difficulty.to_string(),
     )
     .execute(pool)
     .await
@@ -104,5 +198,8 @@ pub async fn insert_applicant(
     Ok(RegisterResponseData {
         token: token.to_string(),
         challenge: challenge.challenge,
+        prompt: challenge_kind.prompt().to_string(),
+        deadline,
+        difficulty,
     })
 }
@@ -0,0 +1,185 @@
+use crate::authentication::HmacSecret;
+use crate::domain::ApplicantToken;
+use crate::errors::ApiError;
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{query, PgPool, Postgres, Transaction};
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct RotateTokenResponseData {
+    pub token: String,
+}
+
+/// The outcome of [`replace_token`].
+pub struct ReplacedToken {
+    pub token: ApplicantToken,
+    pub revoked_at: DateTime<Utc>,
+}
+
+/// Who revoked a token, recorded for auditing.
+pub enum RevokedBy<'a> {
+    Applicant,
+    Admin { key_hash: &'a str },
+    Recovery,
+}
+
+impl RevokedBy<'_> {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RevokedBy::Applicant => "applicant",
+            RevokedBy::Admin { .. } => "admin",
+            RevokedBy::Recovery => "recovery",
+        }
+    }
+
+    fn admin_key_hash(&self) -> Option<&str> {
+        match self {
+            RevokedBy::Applicant | RevokedBy::Recovery => None,
+            RevokedBy::Admin { key_hash } => Some(key_hash),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/rotate_token/{token}",
+    params(("token" = String, Path, description = "The applicant's current token.")),
+    responses(
+        (status = 200, description = "The token was replaced; the old one is revoked.", body = RotateTokenResponseData),
+        (status = 400, description = "Malformed token.", body = ErrorResponseData),
+        (status = 401, description = "The token has already been revoked.", body = ErrorResponseData),
+        (status = 404, description = "No applicant holds the token.", body = ErrorResponseData),
+    )
+)]
+#[tracing::instrument(name = "Rotating applicant token.", skip(token, pool, hmac_secret))]
+pub async fn rotate_token(
+    token: web::Path<String>,
+    pool: web::Data<PgPool>,
+    hmac_secret: web::Data<HmacSecret>,
+) -> Result<HttpResponse, ApiError> {
+    let token = match ApplicantToken::parse(&token) {
+        Ok(token) => token,
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+    let token_hash = token.hash(&hmac_secret.0);
+
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("Failed to begin transaction: {:?}", e);
+            return Err(ApiError::Unexpected(e.to_string()));
+        }
+    };
+
+    let nuid = match query!(
+        r#"SELECT nuid FROM applicants WHERE token_hash=$1 FOR UPDATE"#,
+        token_hash
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    {
+        Some(record) => record.nuid,
+        None => {
+            tracing::error!("No applicant holds the token.");
+            return Err(unknown_token(&pool, &token, &token_hash).await);
+        }
+    };
+
+    let replaced = replace_token(
+        &mut transaction,
+        &nuid,
+        &token_hash,
+        RevokedBy::Applicant,
+        &hmac_secret,
+    )
+    .await?;
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("Failed to commit transaction: {:?}", e);
+        return Err(ApiError::Unexpected(e.to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(RotateTokenResponseData {
+        token: replaced.token.to_string(),
+    }))
+}
+
+/// Issues the applicant a fresh token and records the hash of the old one as
+/// revoked. The caller must hold a lock on the applicant's row. The new token
+/// is returned so it can be shown once; only its hash is stored. The
+/// revocation time is the one the database recorded.
+#[tracing::instrument(
+    name = "Replacing applicant token in the database.",
+    skip(transaction, nuid, old_token_hash, revoked_by, hmac_secret)
+)]
+pub async fn replace_token(
+    transaction: &mut Transaction<'_, Postgres>,
+    nuid: &str,
+    old_token_hash: &str,
+    revoked_by: RevokedBy<'_>,
+    hmac_secret: &HmacSecret,
+) -> Result<ReplacedToken, sqlx::Error> {
+    let new_token = ApplicantToken::generate();
+
+    query!(
+        r#"UPDATE applicants SET token_hash=$2 WHERE nuid=$1"#,
+        nuid,
+        new_token.hash(&hmac_secret.0)
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    let revoked_at = query!(
+        r#"INSERT INTO revoked_tokens (token_hash, nuid, revoked_by, admin_key_hash)
+        VALUES ($1, $2, $3, $4) RETURNING revoked_at;"#,
+        old_token_hash,
+        nuid,
+        revoked_by.as_str(),
+        revoked_by.admin_key_hash(),
+    )
+    .fetch_one(&mut **transaction)
+    .await?
+    .revoked_at;
+
+    Ok(ReplacedToken {
+        token: new_token,
+        revoked_at,
+    })
+}
+
+/// The error for a token that no applicant currently holds: revoked tokens
+/// are told apart from ones that never existed.
+pub async fn unknown_token(pool: &PgPool, token: &ApplicantToken, token_hash: &str) -> ApiError {
+    match retrieve_revocation(pool, token_hash).await {
+        Ok(Some(revoked_at)) => {
+            tracing::error!("Token was revoked at {}", revoked_at);
+            ApiError::TokenRevoked(revoked_at)
+        }
+        Ok(None) => ApiError::NotFound(format!(
+            "Record associated with given token not found! Token: {}",
+            token
+        )),
+        Err(e) => e.into(),
+    }
+}
+
+#[tracing::instrument(
+    name = "Fetching token revocation from the database.",
+    skip(pool, token_hash)
+)]
+pub async fn retrieve_revocation(
+    pool: &PgPool,
+    token_hash: &str,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let record = query!(
+        r#"SELECT revoked_at FROM revoked_tokens WHERE token_hash=$1"#,
+        token_hash
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record.map(|record| record.revoked_at))
+}
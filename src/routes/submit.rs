@@ -2,11 +2,18 @@ use std::time::SystemTime;
 
 use actix_web::{web, HttpResponse};
 use chrono::{DateTime, Utc};
-use sqlx::{query, PgPool};
+use sqlx::{query, PgPool, Postgres, Transaction};
 
-use crate::domain::Nuid;
+use crate::authentication::HmacSecret;
+use crate::configuration::ChallengeSettings;
+use crate::domain::{
+    ApplicantToken, AttemptPolicy, ChallengeKindName, Grade, Nuid, PreviousAttempts,
+};
+use crate::errors::ApiError;
+use crate::routes::rotate_token::unknown_token;
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+#[schema(as = SubmitBodyData)]
 pub struct BodyData(Vec<String>);
 
 impl std::fmt::Display for BodyData {
@@ -23,25 +30,54 @@ impl AsRef<Vec<String>> for BodyData {
 
 pub struct IntermediarySolution {
     pub nuid: String,
+    pub challenge: Vec<String>,
     pub actual_solution: Vec<String>,
+    pub deadline: Option<DateTime<Utc>>,
+    pub challenge_kind: String,
+    pub challenge_parameters: serde_json::Value,
 }
 
 pub struct SolutionToBeChecked {
     pub nuid: Nuid,
+    pub challenge: Vec<String>,
     pub solution: Vec<String>,
+    pub deadline: Option<DateTime<Utc>>,
+    pub challenge_kind: String,
+    pub challenge_parameters: serde_json::Value,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct SubmitResponseData {
     pub correct: bool,
     pub message: String,
+    pub cases_passed: usize,
+    pub cases_total: usize,
+    pub failed_cases: Vec<usize>,
+    pub score: f64,
+    pub remaining_attempts: Option<u32>,
+    /// Whether the submission was made after the deadline, during the grace period.
+    pub late: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/submit/{token}",
+    params(("token" = String, Path, description = "Token issued at registration.")),
+    request_body = SubmitBodyData,
+    responses(
+        (status = 200, description = "The submission was graded.", body = SubmitResponseData),
+        (status = 400, description = "Malformed token or body.", body = ErrorResponseData),
+        (status = 401, description = "The token has been revoked.", body = ErrorResponseData),
+        (status = 403, description = "No attempts remain.", body = ErrorResponseData),
+        (status = 404, description = "No applicant holds the token.", body = ErrorResponseData),
+        (status = 410, description = "The deadline and its grace period have passed.", body = ErrorResponseData),
+        (status = 429, description = "Submitted during the cooldown.", body = ErrorResponseData),
+    )
+)]
 #[tracing::instrument(
     name = "Submit challenge.",
-    skip(token, body, pool),
+    skip(token, body, pool, attempt_policy, challenge_settings, hmac_secret),
     fields(
-        applicant_token = %token,
         applicant_solution = %body
     )
 )]
@@ -49,16 +85,28 @@ pub async fn submit(
     token: web::Path<String>,
     body: web::Json<BodyData>,
     pool: web::Data<PgPool>,
-) -> HttpResponse {
-    let token = match uuid::Uuid::parse_str(&token) {
+    attempt_policy: web::Data<AttemptPolicy>,
+    challenge_settings: web::Data<ChallengeSettings>,
+    hmac_secret: web::Data<HmacSecret>,
+) -> Result<HttpResponse, ApiError> {
+    let token = match ApplicantToken::parse(&token) {
         Ok(token) => token,
-        Err(_) => {
-            tracing::error!("Invalid token! Given: {}", token);
-            return HttpResponse::BadRequest().body(format!("Invalid token! Given: {}", token));
+        Err(err) => {
+            tracing::error!(err);
+            return Err(ApiError::Validation(err));
+        }
+    };
+    let token_hash = token.hash(&hmac_secret.0);
+
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("Failed to begin transaction: {:?}", e);
+            return Err(ApiError::Unexpected(e.to_string()));
         }
     };
 
-    let solution_to_be_checked = match retrieve_solution(&pool, &token).await {
+    let solution_to_be_checked = match retrieve_solution(&mut transaction, &token_hash).await {
         Ok(intermediary_solution) => {
             let nuid = match Nuid::parse(&intermediary_solution.nuid) {
                 Ok(nuid) => nuid,
@@ -67,34 +115,78 @@ pub async fn submit(
                         "Invalid database state for NUID! Given: {}",
                         intermediary_solution.nuid
                     );
-                    return HttpResponse::InternalServerError().finish();
+                    return Err(ApiError::Unexpected(format!(
+                        "Invalid database state for NUID! Given: {}",
+                        intermediary_solution.nuid
+                    )));
                 }
             };
             SolutionToBeChecked {
                 nuid,
+                challenge: intermediary_solution.challenge,
                 solution: intermediary_solution.actual_solution,
+                deadline: intermediary_solution.deadline,
+                challenge_kind: intermediary_solution.challenge_kind,
+                challenge_parameters: intermediary_solution.challenge_parameters,
             }
         }
         Err(sqlx::Error::RowNotFound) => {
-            tracing::error!("Row not found: {:?}", token);
-            return HttpResponse::NotFound().body(format!(
-                "Record associated with given token not found! Token: {}",
-                token
-            ));
+            tracing::error!("No applicant holds the token.");
+            return Err(unknown_token(&pool, &token, &token_hash).await);
         }
-        Err(e) => {
-            tracing::error!("Failed to execute query: {:?}", e);
-            return HttpResponse::InternalServerError().finish();
+        Err(e) => return Err(e.into()),
+    };
+
+    let late = match solution_to_be_checked.deadline {
+        Some(deadline) if Utc::now() > deadline => {
+            if Utc::now() > deadline + challenge_settings.late_grace() {
+                tracing::error!("Challenge expired at {}", deadline);
+                return Err(ApiError::Expired(deadline));
+            }
+            true
         }
+        _ => false,
     };
 
-    let correct = solution_to_be_checked.solution == *body.as_ref();
-    match write_submission(&pool, &solution_to_be_checked.nuid, &correct).await {
-        Ok(_) => (),
-        Err(e) => {
-            tracing::error!("Failed to execute query: {:?}", e);
-            return HttpResponse::InternalServerError().finish();
+    let previous_attempts =
+        match retrieve_previous_attempts(&mut transaction, &solution_to_be_checked.nuid).await {
+            Ok(previous_attempts) => previous_attempts,
+            Err(e) => return Err(e.into()),
+        };
+
+    let remaining_attempts = match attempt_policy.check(&previous_attempts, Utc::now()) {
+        Ok(remaining_attempts) => remaining_attempts,
+        Err(rejection) => {
+            tracing::error!("{}", rejection);
+            return Err(rejection.into());
         }
+    };
+
+    let grade = Grade::compute(&solution_to_be_checked.solution, body.as_ref());
+    let correct = grade.is_correct();
+    // Only recorded for reviewers; the applicant is never told.
+    let misconception = if correct {
+        None
+    } else {
+        diagnose(&solution_to_be_checked, body.as_ref(), &grade)
+    };
+    match write_submission(
+        &mut transaction,
+        &solution_to_be_checked.nuid,
+        body.as_ref(),
+        &grade,
+        misconception.as_deref(),
+        late,
+    )
+    .await
+    {
+        Ok(_) => (),
+        Err(e) => return Err(e.into()),
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("Failed to commit transaction: {:?}", e);
+        return Err(ApiError::Unexpected(e.to_string()));
     }
 
     let response_data = SubmitResponseData {
@@ -104,24 +196,56 @@ pub async fn submit(
         } else {
             "Incorrect Solution".to_string()
         },
+        cases_passed: grade.cases_passed,
+        cases_total: grade.cases_total,
+        score: grade.score(),
+        failed_cases: grade.failed_cases,
+        remaining_attempts,
+        late,
     };
 
-    HttpResponse::Ok().json(response_data)
+    Ok(HttpResponse::Ok().json(response_data))
+}
+
+/// The misconception behind an incorrect submission. A challenge whose kind
+/// can no longer be built is left undiagnosed rather than failing the submission.
+fn diagnose(
+    solution_to_be_checked: &SolutionToBeChecked,
+    submitted: &[String],
+    grade: &Grade,
+) -> Option<String> {
+    let challenge_kind = ChallengeKindName::parse(&solution_to_be_checked.challenge_kind)
+        .and_then(|name| name.challenge_kind(&solution_to_be_checked.challenge_parameters));
+
+    match challenge_kind {
+        Ok(challenge_kind) => challenge_kind.diagnose(
+            &solution_to_be_checked.challenge,
+            submitted,
+            &grade.failed_cases,
+        ),
+        Err(err) => {
+            tracing::error!(err);
+            None
+        }
+    }
 }
 
 #[tracing::instrument(
     name = "Fetching applicant solution from the database.",
-    skip(token, pool)
+    skip(token_hash, transaction)
 )]
 pub async fn retrieve_solution(
-    pool: &PgPool,
-    token: &uuid::Uuid,
+    transaction: &mut Transaction<'_, Postgres>,
+    token_hash: &str,
 ) -> Result<IntermediarySolution, sqlx::Error> {
+    // Locking the applicant serializes concurrent submissions, so attempt
+    // limits cannot be raced.
     let record = query!(
-        r#"SELECT nuid, solution FROM applicants WHERE token=$1"#,
-        token
+        r#"SELECT nuid, challenge, solution, deadline, challenge_kind, challenge_parameters
+        FROM applicants WHERE token_hash=$1 FOR UPDATE"#,
+        token_hash
     )
-    .fetch_one(pool)
+    .fetch_one(&mut **transaction)
     .await
     .map_err(|e| {
         tracing::error!("Failed to execute query: {:?}", e);
@@ -134,28 +258,70 @@ pub async fn retrieve_solution(
 
     Ok(IntermediarySolution {
         nuid: record.nuid,
+        challenge: record.challenge,
         actual_solution: record.solution,
+        deadline: record.deadline,
+        challenge_kind: record.challenge_kind,
+        challenge_parameters: record.challenge_parameters,
+    })
+}
+
+#[tracing::instrument(
+    name = "Fetching previous applicant submissions from the database.",
+    skip(transaction, nuid)
+)]
+pub async fn retrieve_previous_attempts(
+    transaction: &mut Transaction<'_, Postgres>,
+    nuid: &Nuid,
+) -> Result<PreviousAttempts, sqlx::Error> {
+    let record = query!(
+        r#"SELECT COUNT(*) AS "count!", MAX(submission_time) AS last_submission_time
+        FROM submissions WHERE nuid=$1"#,
+        nuid.as_ref()
+    )
+    .fetch_one(&mut **transaction)
+    .await?;
+
+    Ok(PreviousAttempts {
+        count: record.count as u32,
+        last_submission_time: record.last_submission_time,
     })
 }
 
 #[tracing::instrument(
     name = "Saving applicant submission to the database.",
-    skip(pool, nuid, correct)
+    skip(transaction, nuid, answer, grade, misconception, late)
 )]
 pub async fn write_submission(
-    pool: &PgPool,
+    transaction: &mut Transaction<'_, Postgres>,
     nuid: &Nuid,
-    correct: &bool,
+    answer: &[String],
+    grade: &Grade,
+    misconception: Option<&str>,
+    late: bool,
 ) -> Result<(), sqlx::Error> {
     let submission_time: DateTime<Utc> = SystemTime::now().into();
+    let failed_cases = grade
+        .failed_cases
+        .iter()
+        .map(|&i| i as i32)
+        .collect::<Vec<i32>>();
 
     query!(
-        r#"INSERT INTO submissions (nuid, correct, submission_time) VALUES ($1, $2, $3);"#,
+        r#"INSERT INTO submissions (nuid, correct, submission_time, cases_passed, cases_total, failed_cases, score, answer, misconception, late)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);"#,
         nuid.as_ref(),
-        correct,
+        grade.is_correct(),
         submission_time,
+        grade.cases_passed as i32,
+        grade.cases_total as i32,
+        &failed_cases,
+        grade.score(),
+        answer,
+        misconception,
+        late,
     )
-    .execute(pool)
+    .execute(&mut **transaction)
     .await?;
 
     Ok(())
@@ -1,23 +1,172 @@
-use crate::routes::{applicants, challenge, forgot_token, health_check, register, submit};
-use actix_web::{dev::Server, web, App, HttpServer};
+use crate::authentication::HmacSecret;
+use crate::configuration::Settings;
+use crate::errors::ApiError;
+use crate::mailer::Mailer;
+use crate::rate_limiting::RateLimiter;
+use crate::routes::{
+    admin, applicants, challenge, forgot_token, forgot_token_retired, health_check, openapi,
+    register, rotate_token, submit, verify_recovery_code,
+};
+use actix_web::http::Method;
+use actix_web::middleware::DefaultHeaders;
+use actix_web::{dev::Server, web, App, FromRequest, Handler, HttpServer, Responder, Route};
 use sqlx::PgPool;
 use std::net::TcpListener;
+use std::sync::Arc;
 use tracing_actix_web::TracingLogger;
 
-pub fn run(listener: TcpListener, db_pool: PgPool) -> Result<Server, std::io::Error> {
+pub fn run(
+    listener: TcpListener,
+    db_pool: PgPool,
+    settings: Settings,
+    mailer: Arc<dyn Mailer>,
+) -> Result<Server, std::io::Error> {
     let db_pool = web::Data::new(db_pool);
+    let challenge_settings = web::Data::new(settings.challenge);
+    let registration_settings = web::Data::new(settings.registration);
+    let attempt_policy = web::Data::new(settings.submission);
+    // Shared by every worker, so limits hold across the whole process.
+    let rate_limiter = web::Data::new(RateLimiter::new(settings.rate_limit));
+    let mailer: web::Data<dyn Mailer> = web::Data::from(mailer);
+    let recovery_settings = web::Data::new(settings.recovery);
+    let scoring_settings = web::Data::new(settings.scoring);
+    let hmac_secret = web::Data::new(HmacSecret(settings.application.hmac_secret));
     let server = HttpServer::new(move || {
         App::new()
             .wrap(TracingLogger::default())
-            .route("/health_check", web::get().to(health_check))
-            .route("/register", web::post().to(register))
-            .route("/forgot_token/{nuid}", web::get().to(forgot_token))
-            .route("/challenge/{token}", web::get().to(challenge))
-            .route("/submit/{token}", web::post().to(submit))
-            .route("/applicants", web::get().to(applicants))
+            .app_data(web::JsonConfig::default().error_handler(|err, _| {
+                tracing::error!("Invalid JSON body: {}", err);
+                ApiError::Validation(format!("Invalid JSON body! {}", err)).into()
+            }))
+            .app_data(web::QueryConfig::default().error_handler(|err, _| {
+                tracing::error!("Invalid query string: {}", err);
+                ApiError::Validation(format!("Invalid query string! {}", err)).into()
+            }))
+            .configure(|cfg| mount(cfg, root_routes()))
+            .service(web::scope("/v1").configure(|cfg| mount(cfg, v1_routes())))
+            // Unversioned aliases of v1 for scripts written before versioning.
+            // This scope matches every path, so it must be registered last.
+            .service(
+                web::scope("")
+                    .wrap(
+                        DefaultHeaders::new()
+                            .add(("Deprecation", "true"))
+                            .add(("Link", r#"</v1>; rel="successor-version""#)),
+                    )
+                    .configure(|cfg| {
+                        mount(cfg, v1_routes());
+                        mount(cfg, legacy_routes());
+                    }),
+            )
             .app_data(db_pool.clone())
+            .app_data(challenge_settings.clone())
+            .app_data(registration_settings.clone())
+            .app_data(attempt_policy.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(mailer.clone())
+            .app_data(recovery_settings.clone())
+            .app_data(scoring_settings.clone())
+            .app_data(hmac_secret.clone())
     })
     .listen(listener)?
     .run();
     Ok(server)
 }
+
+/// A mounted route. Its method and path are kept next to the handler, so the
+/// routes can be listed without starting the server.
+pub struct ApiRoute {
+    pub method: Method,
+    pub path: &'static str,
+    route: Route,
+}
+
+impl ApiRoute {
+    fn new<F, Args>(method: Method, path: &'static str, handler: F) -> Self
+    where
+        F: Handler<Args>,
+        Args: FromRequest + 'static,
+        F::Output: Responder + 'static,
+    {
+        Self {
+            route: web::method(method.clone()).to(handler),
+            method,
+            path,
+        }
+    }
+}
+
+fn mount(cfg: &mut web::ServiceConfig, routes: Vec<ApiRoute>) {
+    for api_route in routes {
+        cfg.route(api_route.path, api_route.route);
+    }
+}
+
+/// Routes outside of any version.
+pub fn root_routes() -> Vec<ApiRoute> {
+    vec![
+        ApiRoute::new(Method::GET, "/health_check", health_check),
+        ApiRoute::new(Method::GET, "/openapi.json", openapi),
+    ]
+}
+
+/// Routes only on the unversioned scope, for calls v1 replaced in a way an
+/// alias cannot cover.
+pub fn legacy_routes() -> Vec<ApiRoute> {
+    vec![ApiRoute::new(
+        Method::GET,
+        "/forgot_token/{nuid}",
+        forgot_token_retired,
+    )]
+}
+
+/// Routes of version 1 of the API, mounted under `/v1`. A new version with
+/// different payloads gets its own function mounted under its own scope, next
+/// to this one.
+pub fn v1_routes() -> Vec<ApiRoute> {
+    vec![
+        ApiRoute::new(Method::POST, "/register", register),
+        ApiRoute::new(Method::POST, "/forgot_token/{nuid}", forgot_token),
+        ApiRoute::new(
+            Method::POST,
+            "/forgot_token/{nuid}/verify",
+            verify_recovery_code,
+        ),
+        ApiRoute::new(Method::GET, "/challenge/{token}", challenge),
+        ApiRoute::new(Method::POST, "/submit/{token}", submit),
+        ApiRoute::new(Method::POST, "/rotate_token/{token}", rotate_token),
+        ApiRoute::new(Method::GET, "/applicants", applicants),
+        ApiRoute::new(Method::GET, "/admin/applicants", admin::list_applicants),
+        ApiRoute::new(Method::GET, "/admin/stats", admin::stats),
+        ApiRoute::new(
+            Method::GET,
+            "/admin/applicants/export",
+            admin::export_applicants,
+        ),
+        ApiRoute::new(
+            Method::PUT,
+            "/admin/applicants/{nuid}/deadline",
+            admin::extend_deadline,
+        ),
+        ApiRoute::new(
+            Method::POST,
+            "/admin/applicants/{nuid}/rotate_token",
+            admin::rotate_token,
+        ),
+        ApiRoute::new(
+            Method::GET,
+            "/admin/applicants/{nuid}/submissions",
+            admin::submission_history,
+        ),
+        ApiRoute::new(
+            Method::GET,
+            "/admin/applicants/{nuid}/cases/{case}/trace",
+            admin::trace_case,
+        ),
+        ApiRoute::new(
+            Method::GET,
+            "/admin/applicants/{nuid}/challenge/verify",
+            admin::verify_challenge,
+        ),
+    ]
+}
@@ -1,8 +1,11 @@
-use crate::helpers::{register_sample_applicant_with_nuid, spawn_app};
+use crate::helpers::{
+    parse_error, register_sample_applicant_with_nuid, solve, spawn_app, spawn_app_with, TestApp,
+};
 use generate_coding_challenge_server::{
-    domain::{algo_question::parse_barcode, Nuid},
+    domain::{algo_question::parse_barcode, Nuid, ScoringPolicy},
     routes::{applicants::ApplicantsResponseData, RegisterResponseData},
 };
+use uuid::Uuid;
 
 #[tokio::test]
 async fn applicants_returns_a_200_for_valid_nuids_that_exist() {
@@ -35,7 +38,7 @@ async fn applicants_returns_a_200_for_valid_nuids_that_exist() {
     );
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -58,7 +61,7 @@ async fn applicants_returns_a_200_for_valid_nuids_that_exist() {
     let solution_json: serde_json::Value = serde_json::Value::Array(Vec::new());
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -74,12 +77,7 @@ async fn applicants_returns_a_200_for_valid_nuids_that_exist() {
     let body: serde_json::Value =
         serde_json::Value::Array(vec![nuid1.into(), nuid2.into(), nuid3.into()]);
 
-    let response = client
-        .get(&format!("{}/applicants", &app.address))
-        .json(&body)
-        .send()
-        .await
-        .expect("Failed to execute request.");
+    let response = app.get_applicants(&client, &body).await;
 
     assert_eq!(200, response.status().as_u16());
 
@@ -141,12 +139,7 @@ async fn applicants_returns_a_400_for_invalid_nuids() {
         "buzz".into(),
     ]);
 
-    let response = client
-        .get(&format!("{}/applicants", &app.address))
-        .json(&body)
-        .send()
-        .await
-        .expect("Failed to execute request.");
+    let response = app.get_applicants(&client, &body).await;
 
     assert_eq!(400, response.status().as_u16());
 }
@@ -182,7 +175,7 @@ async fn applicants_returns_a_404_for_a_mix_of_valid_nuids_that_do_and_dont_exis
     );
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -205,7 +198,7 @@ async fn applicants_returns_a_404_for_a_mix_of_valid_nuids_that_do_and_dont_exis
     let solution_json: serde_json::Value = serde_json::Value::Array(Vec::new());
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -223,12 +216,7 @@ async fn applicants_returns_a_404_for_a_mix_of_valid_nuids_that_do_and_dont_exis
     let body: serde_json::Value =
         serde_json::Value::Array(vec![nuid1.into(), nuid2.into(), nuid3.into(), nuid4.into()]);
 
-    let response = client
-        .get(&format!("{}/applicants", &app.address))
-        .json(&body)
-        .send()
-        .await
-        .expect("Failed to execute request.");
+    let response = app.get_applicants(&client, &body).await;
 
     assert_eq!(404, response.status().as_u16());
 
@@ -295,12 +283,7 @@ async fn applicants_returns_a_404_for_nuids_that_dont_exist() {
     let body: serde_json::Value =
         serde_json::Value::Array(vec![nuid1.into(), nuid2.into(), nuid3.into()]);
 
-    let response = client
-        .get(&format!("{}/applicants", &app.address))
-        .json(&body)
-        .send()
-        .await
-        .expect("Failed to execute request.");
+    let response = app.get_applicants(&client, &body).await;
 
     assert_eq!(404, response.status().as_u16());
 
@@ -361,7 +344,7 @@ async fn applicants_when_submit_correct_then_incorrect_results_in_incorrect() {
     );
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -372,7 +355,7 @@ async fn applicants_when_submit_correct_then_incorrect_results_in_incorrect() {
     let solution_json: serde_json::Value = serde_json::Value::Array(Vec::new());
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -382,12 +365,7 @@ async fn applicants_when_submit_correct_then_incorrect_results_in_incorrect() {
 
     let body: serde_json::Value = serde_json::Value::Array(vec![nuid1.into()]);
 
-    let response = client
-        .get(&format!("{}/applicants", &app.address))
-        .json(&body)
-        .send()
-        .await
-        .expect("Failed to execute request.");
+    let response = app.get_applicants(&client, &body).await;
 
     assert_eq!(200, response.status().as_u16());
 
@@ -413,3 +391,231 @@ async fn applicants_when_submit_correct_then_incorrect_results_in_incorrect() {
             .correct
     );
 }
+
+#[tokio::test]
+async fn applicants_returns_a_401_without_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let body: serde_json::Value = serde_json::Value::Array(vec!["001234567".into()]);
+
+    let response = client
+        .get(format!("{}/v1/applicants", &app.address))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+    assert_eq!(
+        r#"Bearer realm="admin""#,
+        response.headers()["WWW-Authenticate"]
+    );
+}
+
+#[tokio::test]
+async fn applicants_returns_a_401_for_an_unknown_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let body: serde_json::Value = serde_json::Value::Array(vec!["001234567".into()]);
+
+    let response = client
+        .get(format!("{}/v1/applicants", &app.address))
+        .bearer_auth(Uuid::new_v4().to_string())
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+    let error = parse_error(response).await;
+    assert_eq!("unauthorized", error.code);
+    assert_eq!("Invalid admin API key!", error.message);
+}
+
+#[tokio::test]
+async fn applicants_returns_a_403_for_a_revoked_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    sqlx::query!("UPDATE admin_api_keys SET revoked_at = now()")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to revoke admin API keys.");
+
+    let body: serde_json::Value = serde_json::Value::Array(vec!["001234567".into()]);
+
+    let response = app.get_applicants(&client, &body).await;
+
+    assert_eq!(403, response.status().as_u16());
+    let error = parse_error(response).await;
+    assert_eq!("forbidden", error.code);
+    assert_eq!("Admin API key has been revoked!", error.message);
+}
+
+#[tokio::test]
+async fn applicants_reports_submissions_after_the_deadline_as_late() {
+    let app = spawn_app_with(|c| c.challenge.late_grace_minutes = 30).await;
+
+    let client = reqwest::Client::new();
+
+    for nuid in ["001234567", "007654321"] {
+        let register_response =
+            register_sample_applicant_with_nuid(&client, &app.address, nuid).await;
+
+        assert_eq!(200, register_response.status().as_u16());
+
+        let response: RegisterResponseData =
+            serde_json::from_str(&register_response.text().await.unwrap())
+                .expect("Failed to parse response JSON");
+
+        // Only the second applicant submits after their deadline.
+        if nuid == "007654321" {
+            sqlx::query!(
+                "UPDATE applicants SET deadline = now() - interval '1 minute' WHERE nuid = $1",
+                nuid
+            )
+            .execute(&app.db_pool)
+            .await
+            .expect("Failed to move deadline.");
+        }
+
+        let response = client
+            .post(format!("{}/v1/submit/{}", &app.address, &response.token))
+            .json(&serde_json::Value::Array(Vec::new()))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    let body = serde_json::json!(["001234567", "007654321"]);
+
+    let actual: ApplicantsResponseData = app
+        .get_applicants(&client, &body)
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let late = |nuid: &str| {
+        actual
+            .applicants_found
+            .iter()
+            .find(|applicant| applicant.nuid.as_ref() == nuid)
+            .unwrap()
+            .late
+    };
+    assert!(!late("001234567"));
+    assert!(late("007654321"));
+}
+
+/// Registers an applicant who submits a correct solution and then garbage.
+async fn register_correct_then_garbage(app: &TestApp, client: &reqwest::Client) {
+    let registration = app.register_with_nuid(client, "001234567").await;
+
+    app.submit(client, &registration.token, &solve(&registration))
+        .await;
+    app.submit(client, &registration.token, &["garbage".to_string()])
+        .await;
+}
+
+async fn get_applicants_scored(
+    app: &TestApp,
+    client: &reqwest::Client,
+    scoring: &str,
+) -> ApplicantsResponseData {
+    let response = client
+        .get(format!(
+            "{}/v1/applicants?scoring={}",
+            &app.address, scoring
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .json(&serde_json::json!(["001234567"]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    response
+        .json()
+        .await
+        .expect("Failed to parse response JSON")
+}
+
+#[tokio::test]
+async fn applicants_reports_the_submission_picked_by_the_scoring_policy() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_correct_then_garbage(&app, &client).await;
+
+    let test_cases = vec![
+        ("latest", false, 0.0),
+        ("first_correct", true, 1.0),
+        ("best_score", true, 1.0),
+    ];
+
+    for (scoring, correct, score) in test_cases {
+        let response = get_applicants_scored(&app, &client, scoring).await;
+
+        assert_eq!(scoring, response.scoring.to_string());
+        assert_eq!(1, response.applicants_found.len());
+        assert_eq!(
+            correct, response.applicants_found[0].correct,
+            "Unexpected correctness with the {} policy.",
+            scoring
+        );
+        assert_eq!(Some(score), response.applicants_found[0].score);
+    }
+
+    let first_correct = get_applicants_scored(&app, &client, "first_correct").await;
+    let latest = get_applicants_scored(&app, &client, "latest").await;
+    assert!(
+        first_correct.applicants_found[0].time_to_completion
+            <= latest.applicants_found[0].time_to_completion
+    );
+}
+
+#[tokio::test]
+async fn applicants_uses_the_configured_scoring_policy_by_default() {
+    let app = spawn_app_with(|c| c.scoring.default_policy = ScoringPolicy::FirstCorrect).await;
+
+    let client = reqwest::Client::new();
+
+    register_correct_then_garbage(&app, &client).await;
+
+    let body = serde_json::json!(["001234567"]);
+    let response = app.get_applicants(&client, &body).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: ApplicantsResponseData = response.json().await.unwrap();
+    assert_eq!(ScoringPolicy::FirstCorrect, response.scoring);
+    assert!(response.applicants_found[0].correct);
+}
+
+#[tokio::test]
+async fn applicants_returns_a_400_for_an_unknown_scoring_policy() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/v1/applicants?scoring=median", &app.address))
+        .bearer_auth(&app.admin_api_key)
+        .json(&serde_json::json!(["001234567"]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!("validation_error", parse_error(response).await.code);
+}
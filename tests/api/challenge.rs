@@ -1,4 +1,5 @@
-use crate::helpers::{register_sample_applicant, spawn_app};
+use crate::helpers::{parse_error, register_sample_applicant, spawn_app};
+use generate_coding_challenge_server::authentication::hash_stored_tokens;
 use generate_coding_challenge_server::routes::challenge::ChallengeResponseData;
 use generate_coding_challenge_server::routes::register::RegisterResponseData;
 
@@ -17,8 +18,8 @@ async fn challenge_returns_a_200_for_token_that_exists() {
             .expect("Failed to parse response JSON");
 
     let challenge_response = client
-        .get(&format!(
-            "{}/challenge/{}",
+        .get(format!(
+            "{}/v1/challenge/{}",
             &app.address, &register_response.token
         ))
         .send()
@@ -32,6 +33,7 @@ async fn challenge_returns_a_200_for_token_that_exists() {
             .expect("Failed to parse response JSON");
 
     assert_eq!(challenge_response.challenge, register_response.challenge);
+    assert_eq!(challenge_response.prompt, register_response.prompt);
 }
 
 #[tokio::test]
@@ -43,16 +45,18 @@ async fn challenge_returns_a_400_for_invalid_uuid() {
     let bad_token = "67e55044-10b1-426f-9247-bb680e5fe0c80123456789";
 
     let response = client
-        .get(&format!("{}/challenge/{}", &app.address, &bad_token))
+        .get(format!("{}/v1/challenge/{}", &app.address, &bad_token))
         .send()
         .await
         .expect("Failed to execute request.");
 
     assert_eq!(400, response.status().as_u16());
 
+    let error = parse_error(response).await;
+    assert_eq!("validation_error", error.code);
     assert_eq!(
         format!("Invalid token! Given: {}", &bad_token),
-        response.text().await.unwrap()
+        error.message
     );
 }
 
@@ -65,18 +69,95 @@ async fn challenge_returns_a_404_for_token_that_does_not_exist_in_db() {
     let bad_token = "67e55044-10b1-426f-9247-bb680e5fe0c8";
 
     let response = client
-        .get(&format!("{}/challenge/{}", &app.address, &bad_token))
+        .get(format!("{}/v1/challenge/{}", &app.address, &bad_token))
         .send()
         .await
         .expect("Failed to execute request.");
 
     assert_eq!(404, response.status().as_u16());
 
+    let error = parse_error(response).await;
+    assert_eq!("not_found", error.code);
     assert_eq!(
         format!(
             "Record associated with given token not found! Token: {}",
             &bad_token
         ),
-        response.text().await.unwrap()
+        error.message
     );
 }
+
+#[tokio::test]
+async fn challenge_returns_a_410_after_the_deadline() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    let register_response: RegisterResponseData =
+        serde_json::from_str(&register_response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    sqlx::query!("UPDATE applicants SET deadline = now() - interval '1 minute'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to expire applicant.");
+
+    let response = client
+        .get(format!(
+            "{}/v1/challenge/{}",
+            &app.address, &register_response.token
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(410, response.status().as_u16());
+    let error = parse_error(response).await;
+    assert_eq!("challenge_expired", error.code);
+    assert!(error.message.starts_with("Challenge expired at"));
+    assert!(error.details.unwrap()["deadline"].is_string());
+}
+
+#[tokio::test]
+async fn plaintext_tokens_stored_before_hashing_keep_working() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    // Put the applicant back the way they were stored before tokens were hashed.
+    let token = uuid::Uuid::new_v4();
+    sqlx::query!("UPDATE applicants SET token = $1, token_hash = NULL", token)
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to store plaintext token.");
+
+    let hashed = hash_stored_tokens(&app.db_pool, &app.hmac_secret)
+        .await
+        .expect("Failed to hash stored applicant tokens.");
+
+    assert_eq!(1, hashed);
+
+    let saved = sqlx::query!("SELECT token, token_hash FROM applicants")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved applicant.");
+
+    assert_eq!(saved.token, None);
+    assert!(saved.token_hash.is_some());
+
+    let challenge_response = client
+        .get(format!("{}/v1/challenge/{}", &app.address, token))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, challenge_response.status().as_u16());
+}
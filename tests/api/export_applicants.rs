@@ -0,0 +1,255 @@
+use generate_coding_challenge_server::routes::admin::export::EXPORT_COLUMNS;
+use maplit::hashmap;
+use reqwest::Response;
+
+use crate::helpers::{parse_error, solve, spawn_app, TestApp};
+
+async fn export(app: &TestApp, client: &reqwest::Client, query: &str) -> Response {
+    client
+        .get(format!(
+            "{}/v1/admin/applicants/export?{}",
+            &app.address, query
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+fn parse_csv(body: &str) -> Vec<Vec<String>> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(body.as_bytes())
+        .records()
+        .map(|record| {
+            record
+                .expect("Failed to parse CSV record.")
+                .iter()
+                .map(str::to_string)
+                .collect()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn export_returns_every_applicant_as_csv() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let registration = app.register_with_nuid(&client, "000000001").await;
+
+    let solution = solve(&registration);
+    app.submit(&client, &registration.token, &[]).await;
+    app.submit(&client, &registration.token, &solution).await;
+    app.submit(&client, &registration.token, &[]).await;
+
+    app.register_with_nuid(&client, "000000002").await;
+
+    let response = export(&app, &client, "").await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        "text/csv; charset=utf-8",
+        response.headers().get("Content-Type").unwrap()
+    );
+
+    let rows = parse_csv(&response.text().await.unwrap());
+
+    assert_eq!(3, rows.len());
+    assert_eq!(EXPORT_COLUMNS.to_vec(), rows[0]);
+
+    let column = |row: &Vec<String>, name: &str| {
+        let index = EXPORT_COLUMNS.iter().position(|c| *c == name).unwrap();
+        row[index].clone()
+    };
+
+    let submitted = &rows[1];
+    assert_eq!("000000001", column(submitted, "nuid"));
+    assert_eq!("Garrett", column(submitted, "name"));
+    assert_eq!("3", column(submitted, "attempts"));
+    assert_eq!("0", column(submitted, "first_submission_score"));
+    assert_eq!("1", column(submitted, "best_submission_score"));
+    assert_eq!("0", column(submitted, "last_submission_score"));
+    assert_eq!("false", column(submitted, "correct"));
+    assert!(column(submitted, "best_submission_time") > column(submitted, "first_submission_time"));
+    assert!(column(submitted, "last_submission_time") > column(submitted, "best_submission_time"));
+
+    let not_submitted = &rows[2];
+    assert_eq!("000000002", column(not_submitted, "nuid"));
+    assert_eq!("0", column(not_submitted, "attempts"));
+    assert_eq!("", column(not_submitted, "last_submission_time"));
+    assert_eq!("", column(not_submitted, "correct"));
+    assert_eq!("", column(not_submitted, "time_to_completion_seconds"));
+}
+
+#[tokio::test]
+async fn export_picks_the_best_submission_like_the_best_score_policy() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let registration = app.register_with_nuid(&client, "000000001").await;
+
+    app.submit(&client, &registration.token, &[]).await;
+    app.submit(&client, &registration.token, &[]).await;
+
+    // A correct submission graded before scores were recorded.
+    sqlx::query!(
+        "UPDATE submissions SET correct = true, score = NULL WHERE submission_id =
+        (SELECT MIN(submission_id) FROM submissions)"
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to update submission.");
+
+    let response = export(&app, &client, "").await;
+
+    let rows = parse_csv(&response.text().await.unwrap());
+
+    let column = |name: &str| {
+        let index = EXPORT_COLUMNS.iter().position(|c| *c == name).unwrap();
+        rows[1][index].clone()
+    };
+
+    assert_eq!(
+        column("first_submission_time"),
+        column("best_submission_time")
+    );
+    assert_eq!("", column("best_submission_score"));
+}
+
+#[tokio::test]
+async fn export_reports_the_submission_the_scoring_policy_picks() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let registration = app.register_with_nuid(&client, "000000001").await;
+
+    let solution = solve(&registration);
+    app.submit(&client, &registration.token, &solution).await;
+    app.submit(&client, &registration.token, &[]).await;
+
+    let column = |rows: &Vec<Vec<String>>, name: &str| {
+        let index = EXPORT_COLUMNS.iter().position(|c| *c == name).unwrap();
+        rows[1][index].clone()
+    };
+
+    let response = export(&app, &client, "scoring=latest").await;
+    let latest = parse_csv(&response.text().await.unwrap());
+
+    assert_eq!("false", column(&latest, "correct"));
+
+    let response = export(&app, &client, "scoring=first_correct").await;
+    let first_correct = parse_csv(&response.text().await.unwrap());
+
+    assert_eq!("true", column(&first_correct, "correct"));
+    assert!(
+        column(&first_correct, "time_to_completion_seconds")
+            .parse::<f64>()
+            .unwrap()
+            <= column(&latest, "time_to_completion_seconds")
+                .parse::<f64>()
+                .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn export_returns_a_400_for_an_unknown_scoring_policy() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = export(&app, &client, "scoring=median").await;
+
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn export_escapes_names() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    // Commas, quotes of another kind and leading formula characters all pass
+    // name validation.
+    let name = "=1+1, 'Jr.'\nSmith";
+    let response = client
+        .post(format!("{}/v1/register", &app.address))
+        .json(&hashmap! {
+            "name" => name,
+            "nuid" => "000000001",
+            "email" => "garrett@northeastern.edu",
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response = export(&app, &client, "format=csv").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let rows = parse_csv(&response.text().await.unwrap());
+
+    assert_eq!(2, rows.len());
+    assert_eq!(EXPORT_COLUMNS.len(), rows[1].len());
+    assert_eq!(format!("'{}", name), rows[1][1]);
+}
+
+#[tokio::test]
+async fn export_returns_xlsx_when_asked() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "000000001").await;
+
+    let response = export(&app, &client, "format=xlsx").await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        response.headers().get("Content-Type").unwrap()
+    );
+    assert!(response
+        .headers()
+        .get("Content-Disposition")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("applicants.xlsx"));
+
+    // XLSX files are zip archives.
+    let body = response.bytes().await.unwrap();
+    assert!(body.starts_with(b"PK"));
+}
+
+#[tokio::test]
+async fn export_returns_a_400_for_an_unknown_format() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = export(&app, &client, "format=pdf").await;
+
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!("validation_error", parse_error(response).await.code);
+}
+
+#[tokio::test]
+async fn export_requires_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/v1/admin/applicants/export", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
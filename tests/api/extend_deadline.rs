@@ -0,0 +1,114 @@
+use chrono::{Duration, Utc};
+use generate_coding_challenge_server::routes::admin::deadline::ExtendDeadlineResponseData;
+use generate_coding_challenge_server::routes::RegisterResponseData;
+
+use crate::helpers::{register_sample_applicant, spawn_app};
+
+#[tokio::test]
+async fn extend_deadline_reopens_an_expired_challenge() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    let register_response: RegisterResponseData =
+        serde_json::from_str(&register_response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    sqlx::query!("UPDATE applicants SET deadline = now() - interval '1 minute'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to expire applicant.");
+
+    let new_deadline = Utc::now() + Duration::days(1);
+
+    let response = app
+        .put_deadline(
+            &client,
+            "001234567",
+            &serde_json::json!({ "deadline": new_deadline }),
+        )
+        .await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: ExtendDeadlineResponseData = response.json().await.unwrap();
+
+    assert_eq!(response.nuid, "001234567");
+    assert_eq!(response.deadline, new_deadline);
+
+    let challenge_response = client
+        .get(format!(
+            "{}/v1/challenge/{}",
+            &app.address, &register_response.token
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, challenge_response.status().as_u16());
+}
+
+#[tokio::test]
+async fn extend_deadline_returns_a_400_for_an_earlier_deadline() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    let register_response: RegisterResponseData =
+        serde_json::from_str(&register_response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    let response = app
+        .put_deadline(
+            &client,
+            "001234567",
+            &serde_json::json!({ "deadline": register_response.deadline - Duration::hours(1) }),
+        )
+        .await;
+
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn extend_deadline_returns_a_404_for_nuid_that_does_not_exist_in_db() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = app
+        .put_deadline(
+            &client,
+            "000000000",
+            &serde_json::json!({ "deadline": Utc::now() + Duration::days(1) }),
+        )
+        .await;
+
+    assert_eq!(404, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn extend_deadline_returns_a_401_without_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(format!(
+            "{}/v1/admin/applicants/001234567/deadline",
+            &app.address
+        ))
+        .json(&serde_json::json!({ "deadline": Utc::now() + Duration::days(1) }))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
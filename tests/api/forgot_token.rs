@@ -1,116 +1,293 @@
-use generate_coding_challenge_server::routes::RegisterResponseData;
-use maplit::hashmap;
+use serde_json::Value;
 
-use crate::helpers::spawn_app;
+use crate::helpers::{parse_error, spawn_app, spawn_app_with};
 
 #[tokio::test]
-async fn forgot_token_returns_a_200_for_nuid_that_exists() {
+async fn forgot_token_emails_a_code_that_issues_a_new_token() {
     let app = spawn_app().await;
 
     let client = reqwest::Client::new();
 
-    let nuid = "001234567";
+    let token = app.register_with_nuid(&client, "001234567").await.token;
 
-    let register_response = client
-        .post(&format!("{}/register", &app.address))
-        .json(&hashmap! {
-            "name" => "Garrett",
-            "nuid" => nuid,
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.");
+    let response = app.post_forgot_token(&client, "001234567").await;
+
+    assert_eq!(202, response.status().as_u16());
+
+    let body: Value = response.json().await.unwrap();
+    assert!(!body.to_string().contains(&token));
+
+    let sent = app.mailer.sent();
+    assert_eq!(1, sent.len());
+    assert_eq!("garrett@northeastern.edu", sent[0].to);
+    assert!(!sent[0].body.contains(&token));
+
+    let code = app.last_recovery_code();
+
+    let response = app.post_recovery_code(&client, "001234567", &code).await;
 
-    assert_eq!(200, register_response.status().as_u16());
+    assert_eq!(200, response.status().as_u16());
 
-    let response: RegisterResponseData =
-        serde_json::from_str(&register_response.text().await.unwrap())
-            .expect("Failed to parse response JSON");
+    let body: Value = response.json().await.unwrap();
+    let new_token = body["token"].as_str().unwrap();
+    assert_ne!(token, new_token);
 
-    let token = response.token;
+    let response = client
+        .get(format!("{}/v1/challenge/{}", &app.address, new_token))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(200, response.status().as_u16());
 
     let response = client
-        .get(&format!("{}/forgot_token/{}", &app.address, &nuid))
+        .get(format!("{}/v1/challenge/{}", &app.address, token))
         .send()
         .await
         .expect("Failed to execute request.");
+    assert_eq!(401, response.status().as_u16());
+    assert_eq!("token_revoked", parse_error(response).await.code);
 
-    assert_eq!(200, response.status().as_u16());
+    let saved = sqlx::query!("SELECT revoked_by FROM revoked_tokens")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch revoked token.");
 
-    assert_eq!(response.text().await.unwrap(), token);
+    assert_eq!(saved.revoked_by, "recovery");
 }
 
 #[tokio::test]
-async fn forgot_token_returns_a_400_for_invalid_nuid() {
+async fn forgot_token_responds_the_same_for_unknown_nuids() {
     let app = spawn_app().await;
 
     let client = reqwest::Client::new();
 
-    let nuid = "001234567";
+    app.register_with_nuid(&client, "001234567").await;
 
-    let response = client
-        .post(&format!("{}/register", &app.address))
-        .json(&hashmap! {
-            "name" => "Garrett",
-            "nuid" => nuid,
-        })
-        .send()
+    let known: Value = app
+        .post_forgot_token(&client, "001234567")
         .await
-        .expect("Failed to execute request.");
+        .json()
+        .await
+        .unwrap();
 
-    assert_eq!(200, response.status().as_u16());
+    let response = app.post_forgot_token(&client, "007654321").await;
 
-    let bad_nuid = "a".repeat(9);
+    assert_eq!(202, response.status().as_u16());
 
-    let response = client
-        .get(&format!("{}/forgot_token/{}", &app.address, &bad_nuid))
-        .send()
+    let unknown: Value = response.json().await.unwrap();
+    assert_eq!(known, unknown);
+    assert_eq!(1, app.mailer.sent().len());
+}
+
+#[tokio::test]
+async fn forgot_token_sends_nothing_without_an_email_on_file() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    // Applicants registered before emails were captured have none on file.
+    sqlx::query!("UPDATE applicants SET email = NULL")
+        .execute(&app.db_pool)
         .await
-        .expect("Failed to execute request.");
+        .expect("Failed to clear applicant email.");
+
+    let response = app.post_forgot_token(&client, "001234567").await;
+
+    assert_eq!(202, response.status().as_u16());
+    assert!(app.mailer.sent().is_empty());
+}
+
+#[tokio::test]
+async fn forgot_token_returns_a_400_for_invalid_nuid() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let bad_nuid = "a".repeat(9);
+
+    let response = app.post_forgot_token(&client, &bad_nuid).await;
 
     assert_eq!(400, response.status().as_u16());
-    let expected: serde_json::Value =
-        serde_json::from_str(&format!("\"Invalid NUID! Given: {}\"", &bad_nuid)).unwrap();
-    let actual: serde_json::Value =
-        serde_json::from_str(response.text().await.unwrap().as_str()).unwrap();
-    assert_eq!(expected, actual);
+
+    let error = parse_error(response).await;
+    assert_eq!("validation_error", error.code);
+    assert_eq!(format!("Invalid NUID! Given: {}", &bad_nuid), error.message);
 }
 
 #[tokio::test]
-async fn forgot_token_returns_a_404_for_nuid_that_does_not_exist_in_db() {
+async fn verify_returns_a_403_for_a_wrong_code() {
     let app = spawn_app().await;
 
     let client = reqwest::Client::new();
 
-    let nuid = "001234567";
+    app.register_with_nuid(&client, "001234567").await;
 
-    let response = client
-        .post(&format!("{}/register", &app.address))
-        .json(&hashmap! {
-            "name" => "Garrett",
-            "nuid" => nuid,
-        })
-        .send()
+    app.post_forgot_token(&client, "001234567").await;
+    let code = app.last_recovery_code();
+    let wrong_code = if code == "000000" { "111111" } else { "000000" };
+
+    let response = app
+        .post_recovery_code(&client, "001234567", wrong_code)
+        .await;
+
+    assert_eq!(403, response.status().as_u16());
+    assert_eq!("forbidden", parse_error(response).await.code);
+}
+
+#[tokio::test]
+async fn verify_returns_a_403_without_a_code_being_requested() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    let response = app.post_recovery_code(&client, "001234567", "123456").await;
+
+    assert_eq!(403, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn verify_returns_a_400_for_a_malformed_code() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = app.post_recovery_code(&client, "001234567", "abc").await;
+
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!("validation_error", parse_error(response).await.code);
+}
+
+#[tokio::test]
+async fn codes_can_only_be_used_once() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    app.post_forgot_token(&client, "001234567").await;
+    let code = app.last_recovery_code();
+
+    let response = app.post_recovery_code(&client, "001234567", &code).await;
+    assert_eq!(200, response.status().as_u16());
+
+    let response = app.post_recovery_code(&client, "001234567", &code).await;
+    assert_eq!(403, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn codes_are_invalidated_after_too_many_wrong_guesses() {
+    let app = spawn_app_with(|c| c.recovery.max_code_attempts = 2).await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    app.post_forgot_token(&client, "001234567").await;
+    let code = app.last_recovery_code();
+    let wrong_code = if code == "000000" { "111111" } else { "000000" };
+
+    for _ in 0..2 {
+        let response = app
+            .post_recovery_code(&client, "001234567", wrong_code)
+            .await;
+        assert_eq!(403, response.status().as_u16());
+    }
+
+    let response = app.post_recovery_code(&client, "001234567", &code).await;
+
+    assert_eq!(403, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn requesting_a_new_code_does_not_restore_the_guess_budget() {
+    let app = spawn_app_with(|c| {
+        c.recovery.max_code_attempts = 2;
+        // Stay clear of the per-NUID limit so only the guess budget applies.
+        c.rate_limit.per_nuid.capacity = 10;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    app.post_forgot_token(&client, "001234567").await;
+    let code = app.last_recovery_code();
+    let wrong_code = if code == "000000" { "111111" } else { "000000" };
+
+    for _ in 0..2 {
+        let response = app
+            .post_recovery_code(&client, "001234567", wrong_code)
+            .await;
+        assert_eq!(403, response.status().as_u16());
+    }
+
+    let response = app.post_forgot_token(&client, "001234567").await;
+    assert_eq!(202, response.status().as_u16());
+    let code = app.last_recovery_code();
+
+    let response = app.post_recovery_code(&client, "001234567", &code).await;
+
+    assert_eq!(403, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn requesting_a_new_code_after_the_old_one_expires_restores_the_guess_budget() {
+    let app = spawn_app_with(|c| {
+        c.recovery.max_code_attempts = 2;
+        // Stay clear of the per-NUID limit so only the guess budget applies.
+        c.rate_limit.per_nuid.capacity = 10;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    app.post_forgot_token(&client, "001234567").await;
+    let code = app.last_recovery_code();
+    let wrong_code = if code == "000000" { "111111" } else { "000000" };
+
+    for _ in 0..2 {
+        app.post_recovery_code(&client, "001234567", wrong_code)
+            .await;
+    }
+
+    sqlx::query!("UPDATE recovery_codes SET expires_at = now() - interval '1 minute'")
+        .execute(&app.db_pool)
         .await
-        .expect("Failed to execute request.");
+        .expect("Failed to expire recovery code.");
+
+    app.post_forgot_token(&client, "001234567").await;
+    let code = app.last_recovery_code();
+
+    let response = app.post_recovery_code(&client, "001234567", &code).await;
 
     assert_eq!(200, response.status().as_u16());
+}
 
-    let bad_nuid = "0".repeat(9);
+#[tokio::test]
+async fn expired_codes_are_rejected() {
+    let app = spawn_app().await;
 
-    let response = client
-        .get(&format!("{}/forgot_token/{}", &app.address, &bad_nuid))
-        .send()
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    app.post_forgot_token(&client, "001234567").await;
+    let code = app.last_recovery_code();
+
+    sqlx::query!("UPDATE recovery_codes SET expires_at = now() - interval '1 minute'")
+        .execute(&app.db_pool)
         .await
-        .expect("Failed to execute request.");
+        .expect("Failed to expire recovery code.");
 
-    assert_eq!(404, response.status().as_u16());
+    let response = app.post_recovery_code(&client, "001234567", &code).await;
 
-    assert_eq!(
-        format!(
-            "Record associated with given NUID not found! NUID: {}",
-            &bad_nuid
-        ),
-        response.text().await.unwrap()
-    );
+    assert_eq!(403, response.status().as_u16());
 }
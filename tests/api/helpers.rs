@@ -1,11 +1,22 @@
-use generate_coding_challenge_server::configuration::{get_configuration, DatabaseSettings};
+use generate_coding_challenge_server::authentication::{
+    hash_stored_tokens, store_admin_api_keys, HmacSecret,
+};
+use generate_coding_challenge_server::configuration::{
+    get_configuration, DatabaseSettings, Settings,
+};
+use generate_coding_challenge_server::domain::parse_barcode;
+use generate_coding_challenge_server::errors::ErrorResponseData;
+use generate_coding_challenge_server::mailer::InMemoryMailer;
+use generate_coding_challenge_server::routes::{RegisterResponseData, SubmitResponseData};
 use generate_coding_challenge_server::startup::run;
 use generate_coding_challenge_server::telemetry::{get_subscriber, init_subscriber};
 use maplit::hashmap;
 use once_cell::sync::Lazy;
 use reqwest::Response;
+use secrecy::Secret;
 use sqlx::{Connection, Executor, PgConnection, PgPool};
 use std::net::TcpListener;
+use std::sync::Arc;
 use uuid::Uuid;
 
 static TRACING: Lazy<()> = Lazy::new(|| {
@@ -24,9 +35,134 @@ static TRACING: Lazy<()> = Lazy::new(|| {
 pub struct TestApp {
     pub address: String,
     pub db_pool: PgPool,
+    pub admin_api_key: String,
+    pub mailer: Arc<InMemoryMailer>,
+    pub hmac_secret: HmacSecret,
+}
+
+impl TestApp {
+    pub async fn get_applicants(
+        &self,
+        client: &reqwest::Client,
+        body: &serde_json::Value,
+    ) -> Response {
+        client
+            .get(format!("{}/v1/applicants", &self.address))
+            .bearer_auth(&self.admin_api_key)
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn list_applicants(&self, client: &reqwest::Client, query: &str) -> Response {
+        client
+            .get(format!("{}/v1/admin/applicants?{}", &self.address, query))
+            .bearer_auth(&self.admin_api_key)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// Registers the sample applicant under `nuid`, asserting it succeeds.
+    pub async fn register_with_nuid(
+        &self,
+        client: &reqwest::Client,
+        nuid: &str,
+    ) -> RegisterResponseData {
+        let response = register_sample_applicant_with_nuid(client, &self.address, nuid).await;
+
+        assert_eq!(200, response.status().as_u16());
+
+        response
+            .json()
+            .await
+            .expect("Failed to parse response JSON")
+    }
+
+    /// Submits `solution` with `token`, asserting it is accepted.
+    pub async fn submit(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        solution: &[String],
+    ) -> SubmitResponseData {
+        let response = client
+            .post(format!("{}/v1/submit/{}", &self.address, token))
+            .json(&solution)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(200, response.status().as_u16());
+
+        response
+            .json()
+            .await
+            .expect("Failed to parse response JSON")
+    }
+
+    pub async fn put_deadline(
+        &self,
+        client: &reqwest::Client,
+        nuid: &str,
+        body: &serde_json::Value,
+    ) -> Response {
+        client
+            .put(format!(
+                "{}/v1/admin/applicants/{}/deadline",
+                &self.address, nuid
+            ))
+            .bearer_auth(&self.admin_api_key)
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_forgot_token(&self, client: &reqwest::Client, nuid: &str) -> Response {
+        client
+            .post(format!("{}/v1/forgot_token/{}", &self.address, nuid))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_recovery_code(
+        &self,
+        client: &reqwest::Client,
+        nuid: &str,
+        code: &str,
+    ) -> Response {
+        client
+            .post(format!("{}/v1/forgot_token/{}/verify", &self.address, nuid))
+            .json(&hashmap! { "code" => code })
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// The code from the most recent recovery email.
+    pub fn last_recovery_code(&self) -> String {
+        let email = self.mailer.sent().pop().expect("No email was sent.");
+
+        email
+            .body
+            .split_whitespace()
+            .find_map(|word| {
+                let word = word.trim_end_matches('.');
+                (word.len() == 6 && word.chars().all(|c| c.is_ascii_digit()))
+                    .then(|| word.to_string())
+            })
+            .expect("No recovery code in the email.")
+    }
 }
 
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with(|_| {}).await
+}
+
+pub async fn spawn_app_with(configure: impl FnOnce(&mut Settings)) -> TestApp {
     Lazy::force(&TRACING);
 
     let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind to random port.");
@@ -35,15 +171,35 @@ pub async fn spawn_app() -> TestApp {
 
     let mut configuration = get_configuration().expect("Failed to read configuration.");
     configuration.database.database_name = Uuid::new_v4().to_string();
+    let admin_api_key = Uuid::new_v4().to_string();
+    configuration.admin.api_keys = vec![Secret::new(admin_api_key.clone())];
+    configure(&mut configuration);
 
     let connection_pool = configure_database(&configuration.database).await;
+    store_admin_api_keys(&connection_pool, &configuration.admin)
+        .await
+        .expect("Failed to store admin API keys.");
+    let hmac_secret = HmacSecret(configuration.application.hmac_secret.clone());
+    hash_stored_tokens(&connection_pool, &hmac_secret)
+        .await
+        .expect("Failed to hash stored applicant tokens.");
 
-    let server =
-        run(listener, connection_pool.clone()).expect("Failed to bind address to random port.");
+    let mailer = Arc::new(InMemoryMailer::default());
+
+    let server = run(
+        listener,
+        connection_pool.clone(),
+        configuration,
+        mailer.clone(),
+    )
+    .expect("Failed to bind address to random port.");
     std::mem::drop(tokio::spawn(server));
     TestApp {
         address,
         db_pool: connection_pool,
+        admin_api_key,
+        mailer,
+        hmac_secret,
     }
 }
 
@@ -77,12 +233,29 @@ pub async fn register_sample_applicant_with_nuid(
     nuid: &str,
 ) -> Response {
     client
-        .post(&format!("{}/register", address))
+        .post(format!("{}/v1/register", address))
         .json(&hashmap! {
             "name" => "Garrett",
             "nuid" => nuid,
+            "email" => "garrett@northeastern.edu",
         })
         .send()
         .await
         .expect("Failed to execute request.")
 }
+
+/// The correct solution to an applicant's challenge.
+pub fn solve(registration: &RegisterResponseData) -> Vec<String> {
+    registration
+        .challenge
+        .iter()
+        .map(|case| parse_barcode(case))
+        .collect()
+}
+
+pub async fn parse_error(response: Response) -> ErrorResponseData {
+    response
+        .json()
+        .await
+        .expect("Failed to parse error response JSON")
+}
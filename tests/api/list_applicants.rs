@@ -0,0 +1,237 @@
+use generate_coding_challenge_server::routes::admin::applicants::ListApplicantsResponseData;
+
+use crate::helpers::{parse_error, solve, spawn_app, TestApp};
+
+async fn list(app: &TestApp, client: &reqwest::Client, query: &str) -> ListApplicantsResponseData {
+    let response = app.list_applicants(client, query).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    response
+        .json()
+        .await
+        .expect("Failed to parse response JSON")
+}
+
+fn nuids(response: &ListApplicantsResponseData) -> Vec<&str> {
+    response
+        .applicants
+        .iter()
+        .map(|applicant| applicant.nuid.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn list_applicants_pages_through_the_cohort_by_cursor() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    for nuid in ["000000003", "000000001", "000000002"] {
+        app.register_with_nuid(&client, nuid).await;
+    }
+
+    let first = list(&app, &client, "limit=2").await;
+
+    assert_eq!(vec!["000000003", "000000001"], nuids(&first));
+
+    let cursor = first.next_cursor.expect("Missing next cursor.");
+    // Applicants registering between pages do not shift them.
+    app.register_with_nuid(&client, "000000000").await;
+
+    let second = list(&app, &client, &format!("limit=2&cursor={}", cursor)).await;
+
+    assert_eq!(vec!["000000002", "000000000"], nuids(&second));
+    assert!(second.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn list_applicants_pages_by_page_number() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    for nuid in ["000000001", "000000002", "000000003"] {
+        app.register_with_nuid(&client, nuid).await;
+    }
+
+    let response = list(&app, &client, "limit=2&page=2").await;
+
+    assert_eq!(vec!["000000003"], nuids(&response));
+    assert!(response.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn list_applicants_filters_by_difficulty() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "000000001").await;
+    app.register_with_nuid(&client, "000000002").await;
+
+    sqlx::query!("UPDATE applicants SET difficulty = 'easy' WHERE nuid = '000000002'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to update difficulty.");
+
+    let response = list(&app, &client, "difficulty=easy").await;
+    assert_eq!(vec!["000000002"], nuids(&response));
+    assert_eq!("easy", response.applicants[0].difficulty);
+
+    let response = list(&app, &client, "difficulty=standard").await;
+    assert_eq!(vec!["000000001"], nuids(&response));
+}
+
+#[tokio::test]
+async fn list_applicants_filters_by_submission() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let correct = app.register_with_nuid(&client, "000000001").await;
+    app.submit(&client, &correct.token, &solve(&correct)).await;
+    let incorrect = app.register_with_nuid(&client, "000000002").await;
+    app.submit(&client, &incorrect.token, &[]).await;
+    app.register_with_nuid(&client, "000000003").await;
+
+    let response = list(&app, &client, "submitted=true").await;
+    assert_eq!(vec!["000000001", "000000002"], nuids(&response));
+
+    let response = list(&app, &client, "submitted=false").await;
+    assert_eq!(vec!["000000003"], nuids(&response));
+    assert!(!response.applicants[0].submitted);
+    assert_eq!(None, response.applicants[0].correct);
+    assert!(response.applicants[0].time_to_completion.is_none());
+
+    let response = list(&app, &client, "correct=true").await;
+    assert_eq!(vec!["000000001"], nuids(&response));
+    assert_eq!(Some(true), response.applicants[0].correct);
+    assert_eq!(Some(false), response.applicants[0].late);
+}
+
+#[tokio::test]
+async fn list_applicants_filters_by_registration_time() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "000000001").await;
+    app.register_with_nuid(&client, "000000002").await;
+
+    sqlx::query!(
+        "UPDATE applicants SET registration_time = '2023-01-01T00:00:00Z' WHERE nuid = '000000001'"
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to update registration time.");
+
+    let response = list(&app, &client, "registered_before=2023-06-01T00:00:00Z").await;
+    assert_eq!(vec!["000000001"], nuids(&response));
+
+    let response = list(&app, &client, "registered_after=2023-06-01T00:00:00Z").await;
+    assert_eq!(vec!["000000002"], nuids(&response));
+}
+
+#[tokio::test]
+async fn list_applicants_sorts_by_time_to_completion() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "000000001").await;
+    let slow = app.register_with_nuid(&client, "000000002").await;
+    app.submit(&client, &slow.token, &solve(&slow)).await;
+    let fast = app.register_with_nuid(&client, "000000003").await;
+    app.submit(&client, &fast.token, &solve(&fast)).await;
+
+    sqlx::query!(
+        "UPDATE applicants SET registration_time = registration_time - interval '1 hour'
+        WHERE nuid = '000000002'"
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to update registration time.");
+
+    let first = list(&app, &client, "sort=time_to_completion&limit=2").await;
+    assert_eq!(vec!["000000003", "000000002"], nuids(&first));
+
+    let cursor = first.next_cursor.expect("Missing next cursor.");
+    let second = list(
+        &app,
+        &client,
+        &format!("sort=time_to_completion&limit=2&cursor={}", cursor),
+    )
+    .await;
+    assert_eq!(vec!["000000001"], nuids(&second));
+}
+
+#[tokio::test]
+async fn list_applicants_returns_a_400_for_invalid_pagination() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let test_cases = vec![
+        ("limit=0", "a limit of 0"),
+        ("limit=201", "a limit over the maximum"),
+        ("page=0", "page 0"),
+        (
+            "page=1&cursor=registration_time:0:000000001",
+            "both page and cursor",
+        ),
+        ("cursor=garbage", "a malformed cursor"),
+        (
+            "sort=time_to_completion&cursor=registration_time:0:000000001",
+            "a cursor from another sort",
+        ),
+        ("sort=name", "an unknown sort"),
+        ("submitted=maybe", "a non boolean filter"),
+    ];
+
+    for (query, description) in test_cases {
+        let response = app.list_applicants(&client, query).await;
+
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not fail with 400 Bad Request when given {}.",
+            description
+        );
+        assert_eq!("validation_error", parse_error(response).await.code);
+    }
+}
+
+#[tokio::test]
+async fn list_applicants_requires_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/v1/admin/applicants", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn list_applicants_filters_by_the_submission_the_scoring_policy_picks() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let registration = app.register_with_nuid(&client, "000000001").await;
+    app.submit(&client, &registration.token, &solve(&registration))
+        .await;
+    app.submit(&client, &registration.token, &[]).await;
+
+    let response = list(&app, &client, "correct=true").await;
+    assert!(response.applicants.is_empty());
+
+    let response = list(&app, &client, "correct=true&scoring=best_score").await;
+    assert_eq!(vec!["000000001"], nuids(&response));
+    assert_eq!(Some(1.0), response.applicants[0].score);
+}
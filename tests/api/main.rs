@@ -1,7 +1,18 @@
 mod applicants;
 mod challenge;
+mod export_applicants;
+mod extend_deadline;
 mod forgot_token;
 mod health_check;
 mod helpers;
+mod list_applicants;
+mod openapi;
+mod rate_limiting;
 mod register;
+mod rotate_token;
+mod stats;
+mod submission_history;
 mod submit;
+mod trace_case;
+mod verify_challenge;
+mod versioning;
@@ -0,0 +1,71 @@
+use generate_coding_challenge_server::startup::{root_routes, v1_routes};
+
+use crate::helpers::spawn_app;
+
+/// Every `(method, path)` the server mounts, from the same tables `run` mounts.
+fn mounted_routes() -> Vec<(String, String)> {
+    let root = root_routes()
+        .into_iter()
+        .map(|route| (route.method, route.path.to_string()));
+    let v1 = v1_routes()
+        .into_iter()
+        .map(|route| (route.method, format!("/v1{}", route.path)));
+
+    root.chain(v1)
+        .map(|(method, path)| (method.as_str().to_lowercase(), path))
+        .collect()
+}
+
+#[tokio::test]
+async fn openapi_json_is_served() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/openapi.json", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let spec: serde_json::Value = response.json().await.expect("Failed to parse spec JSON");
+
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+    assert!(spec["components"]["schemas"]["RegisterResponseData"].is_object());
+    assert!(spec["components"]["securitySchemes"]["admin_api_key"].is_object());
+}
+
+#[tokio::test]
+async fn every_route_is_documented() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let spec: serde_json::Value = client
+        .get(format!("{}/openapi.json", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json()
+        .await
+        .expect("Failed to parse spec JSON");
+
+    let routes = mounted_routes();
+
+    assert!(!routes.is_empty());
+
+    for (method, path) in routes {
+        if path == "/openapi.json" {
+            continue;
+        }
+
+        assert!(
+            spec["paths"][&path][&method].is_object(),
+            "{} {} is mounted but not documented in the OpenAPI spec.",
+            method.to_uppercase(),
+            path
+        );
+    }
+}
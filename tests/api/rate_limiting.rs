@@ -0,0 +1,138 @@
+use crate::helpers::{parse_error, register_sample_applicant_with_nuid, spawn_app_with};
+
+#[tokio::test]
+async fn forgot_token_returns_a_429_once_a_nuid_exhausts_its_bucket() {
+    let app = spawn_app_with(|c| {
+        c.rate_limit.per_nuid.capacity = 2;
+        c.rate_limit.per_nuid.refill_seconds = 60;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let nuid = "001234567";
+
+    for _ in 0..2 {
+        let response = app.post_forgot_token(&client, nuid).await;
+        assert_eq!(202, response.status().as_u16());
+    }
+
+    let response = app.post_forgot_token(&client, nuid).await;
+
+    assert_eq!(429, response.status().as_u16());
+
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After was not a number of seconds.");
+
+    assert!(retry_after > 0 && retry_after <= 60);
+    assert_eq!("rate_limited", parse_error(response).await.code);
+
+    let response = app.post_forgot_token(&client, "007654321").await;
+
+    assert_eq!(202, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn forgot_token_returns_a_429_once_a_client_exhausts_its_bucket() {
+    let app = spawn_app_with(|c| {
+        c.rate_limit.per_ip.capacity = 3;
+        c.rate_limit.per_ip.refill_seconds = 60;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    for nuid in ["001234561", "001234562", "001234563"] {
+        let response = app.post_forgot_token(&client, nuid).await;
+        assert_eq!(202, response.status().as_u16());
+    }
+
+    let response = app.post_forgot_token(&client, "001234564").await;
+
+    assert_eq!(429, response.status().as_u16());
+    assert!(response.headers().get("Retry-After").is_some());
+}
+
+#[tokio::test]
+async fn register_returns_a_429_once_a_client_exhausts_its_bucket() {
+    let app = spawn_app_with(|c| {
+        c.rate_limit.per_ip.capacity = 1;
+        c.rate_limit.per_ip.refill_seconds = 60;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant_with_nuid(&client, &app.address, "001234567").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response = register_sample_applicant_with_nuid(&client, &app.address, "007654321").await;
+
+    assert_eq!(429, response.status().as_u16());
+
+    let saved = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM applicants"#,)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to count applicants.");
+
+    assert_eq!(saved.count, 1);
+}
+
+#[tokio::test]
+async fn spoofed_forwarded_for_entries_share_the_proxys_bucket() {
+    let app = spawn_app_with(|c| {
+        c.rate_limit.trust_forwarded_for = true;
+        c.rate_limit.per_ip.capacity = 1;
+        c.rate_limit.per_ip.refill_seconds = 60;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    // The client makes up the left-most entry; the proxy appends the right-most.
+    for (spoofed, expected) in [("1.1.1.1", 202), ("2.2.2.2", 429)] {
+        let response = client
+            .post(format!("{}/v1/forgot_token/{}", &app.address, "001234567"))
+            .header("X-Forwarded-For", format!("{}, 203.0.113.7", spoofed))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(expected, response.status().as_u16());
+    }
+}
+
+#[tokio::test]
+async fn register_charges_the_client_for_invalid_requests() {
+    let app = spawn_app_with(|c| {
+        c.registration.allowed_email_domains = vec!["northeastern.edu".to_string()];
+        c.rate_limit.per_ip.capacity = 2;
+        c.rate_limit.per_ip.refill_seconds = 60;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let bodies = [
+        serde_json::json!({ "name": "Garrett", "nuid": "001234567", "email": "garrett@example.com" }),
+        serde_json::json!({ "name": "Garrett" }),
+    ];
+    for body in &bodies {
+        let response = client
+            .post(format!("{}/v1/register", &app.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(400, response.status().as_u16());
+    }
+
+    let response = register_sample_applicant_with_nuid(&client, &app.address, "001234567").await;
+
+    assert_eq!(429, response.status().as_u16());
+}
@@ -1,7 +1,8 @@
+use generate_coding_challenge_server::domain::{ChallengeKindName, Difficulty};
 use generate_coding_challenge_server::routes::RegisterResponseData;
 use maplit::hashmap;
 
-use crate::helpers::{register_sample_applicant, spawn_app};
+use crate::helpers::{parse_error, register_sample_applicant, spawn_app, spawn_app_with};
 
 #[tokio::test]
 async fn register_returns_a_200_for_valid_request_body() {
@@ -20,13 +21,112 @@ async fn register_returns_a_200_for_valid_request_body() {
     let num_random = 256;
     assert_eq!(response.challenge.len(), num_mandatory + num_random);
 
-    let saved = sqlx::query!("SELECT applicant_name, nuid FROM applicants",)
+    let saved = sqlx::query!("SELECT applicant_name, nuid, email, challenge_kind FROM applicants",)
         .fetch_one(&app.db_pool)
         .await
         .expect("Failed to fetch saved applicant.");
 
     assert_eq!(saved.applicant_name, "Garrett");
     assert_eq!(saved.nuid, "001234567");
+    assert_eq!(saved.email.as_deref(), Some("garrett@northeastern.edu"));
+    assert_eq!(saved.challenge_kind, "barcode");
+}
+
+#[tokio::test]
+async fn register_stores_only_a_hash_of_the_token() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: RegisterResponseData = serde_json::from_str(&response.text().await.unwrap())
+        .expect("Failed to parse response JSON");
+
+    let saved = sqlx::query!("SELECT token, token_hash FROM applicants")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved applicant.");
+
+    assert_eq!(saved.token, None);
+    let token_hash = saved.token_hash.unwrap();
+    assert!(!token_hash.contains(&response.token));
+}
+
+#[tokio::test]
+async fn register_uses_the_configured_challenge_parameters() {
+    let app = spawn_app_with(|c| {
+        c.challenge.parameters = serde_json::json!({
+            "num_random_cases": 10,
+            "num_digits": { "min": 4, "max": 6 },
+            "num_instructions": { "min": 1, "max": 2 },
+            "mandatory_cases": ["##"],
+        });
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: RegisterResponseData = serde_json::from_str(&response.text().await.unwrap())
+        .expect("Failed to parse response JSON");
+
+    assert_eq!(response.challenge.len(), 11);
+    assert!(response.challenge.contains(&"##".to_string()));
+    assert!(response
+        .challenge
+        .iter()
+        .filter(|case| case.as_str() != "##")
+        .all(|case| (4..=6).contains(&case.chars().filter(|c| c.is_ascii_digit()).count())));
+
+    let saved = sqlx::query!("SELECT challenge_parameters FROM applicants",)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved applicant.");
+
+    assert_eq!(saved.challenge_parameters["num_random_cases"], 10);
+}
+
+#[tokio::test]
+async fn register_persists_a_seed_that_regenerates_the_challenge() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: RegisterResponseData = serde_json::from_str(&response.text().await.unwrap())
+        .expect("Failed to parse response JSON");
+
+    let saved = sqlx::query!(
+        "SELECT challenge_kind, seed, generator_version, challenge_parameters, challenge, solution
+        FROM applicants",
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .expect("Failed to fetch saved applicant.");
+
+    let regenerated = ChallengeKindName::parse(&saved.challenge_kind)
+        .unwrap()
+        .regenerate_challenge(
+            saved.seed.expect("Seed was not persisted.") as u64,
+            saved
+                .generator_version
+                .expect("Generator version was not persisted."),
+            &saved.challenge_parameters,
+        )
+        .expect("Failed to regenerate challenge.");
+
+    assert_eq!(regenerated.challenge, response.challenge);
+    assert_eq!(regenerated.challenge, saved.challenge);
+    assert_eq!(regenerated.solution, saved.solution);
 }
 
 #[tokio::test]
@@ -37,21 +137,30 @@ async fn register_returns_a_400_when_request_body_properties_are_missing() {
         (
             hashmap! {
                 "name" => "Garrett",
+                "email" => "garrett@northeastern.edu",
             },
             "missing the nuid",
         ),
         (
             hashmap! {
                 "nuid" => "001234567",
+                "email" => "garrett@northeastern.edu",
             },
             "missing the name",
         ),
-        (hashmap! {}, "missing both name and nuid"),
+        (
+            hashmap! {
+                "name" => "Garrett",
+                "nuid" => "001234567",
+            },
+            "missing the email",
+        ),
+        (hashmap! {}, "missing every field"),
     ];
 
     for (invalid_body, reason) in test_cases {
         let response = client
-            .post(&format!("{}/register", &app.address))
+            .post(format!("{}/v1/register", &app.address))
             .json(&invalid_body)
             .send()
             .await
@@ -75,6 +184,7 @@ async fn register_returns_a_400_when_fields_are_present_but_invalid() {
             hashmap! {
                 "name" => "",
                 "nuid" => "001234567",
+                "email" => "garrett@northeastern.edu",
             },
             "Invalid name! Given: ",
         ),
@@ -82,13 +192,23 @@ async fn register_returns_a_400_when_fields_are_present_but_invalid() {
             hashmap! {
                 "name" => "Garrett",
                 "nuid" => "",
+                "email" => "garrett@northeastern.edu",
             },
             "Invalid NUID! Given: ",
         ),
+        (
+            hashmap! {
+                "name" => "Garrett",
+                "nuid" => "001234567",
+                "email" => "garrett.northeastern.edu",
+            },
+            "Invalid email! Given: garrett.northeastern.edu",
+        ),
         (
             hashmap! {
                 "name" => "",
                 "nuid" => "",
+                "email" => "",
             },
             "Invalid name! Given: ",
         ),
@@ -96,7 +216,7 @@ async fn register_returns_a_400_when_fields_are_present_but_invalid() {
 
     for (invalid_body, error_message) in test_cases {
         let response = client
-            .post(&format!("{}/register", &app.address))
+            .post(format!("{}/v1/register", &app.address))
             .json(&invalid_body)
             .send()
             .await
@@ -108,9 +228,9 @@ async fn register_returns_a_400_when_fields_are_present_but_invalid() {
             "The API did not fail with 400 Bad Request when the payload was {}.",
             error_message
         );
-        let expected: String = serde_json::from_str(&format!("\"{}\"", error_message)).unwrap();
-        let actual: String = serde_json::from_str(response.text().await.unwrap().as_str()).unwrap();
-        assert_eq!(expected, actual);
+        let error = parse_error(response).await;
+        assert_eq!("validation_error", error.code);
+        assert_eq!(error_message, error.message);
     }
 }
 
@@ -128,8 +248,164 @@ async fn register_returns_a_409_for_user_that_already_exists() {
 
     assert_eq!(409, response.status().as_u16());
 
+    let error = parse_error(response).await;
+    assert_eq!("conflict", error.code);
     assert_eq!(
         "NUID 001234567 has already registered! Use the forgot-token endpoint to retrieve your token.",
-        response.text().await.unwrap()
+        error.message
     );
 }
+
+#[tokio::test]
+async fn register_returns_a_400_for_an_email_outside_the_allowed_domains() {
+    let app = spawn_app_with(|c| {
+        c.registration.allowed_email_domains = vec!["northeastern.edu".to_string()];
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/v1/register", &app.address))
+        .json(&hashmap! {
+            "name" => "Garrett",
+            "nuid" => "001234567",
+            "email" => "garrett@gmail.com",
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(400, response.status().as_u16());
+
+    let error = parse_error(response).await;
+    assert_eq!("validation_error", error.code);
+    assert!(error.message.starts_with("Email domain not allowed!"));
+
+    let response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn register_issues_the_requested_difficulty() {
+    let app =
+        spawn_app_with(|c| c.challenge.selectable_difficulties = vec![Difficulty::Hard]).await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/v1/register", &app.address))
+        .json(&hashmap! {
+            "name" => "Garrett",
+            "nuid" => "001234567",
+            "email" => "garrett@northeastern.edu",
+            "difficulty" => "hard",
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: RegisterResponseData = response.json().await.unwrap();
+
+    assert_eq!(Difficulty::Hard, response.difficulty);
+
+    let saved = sqlx::query!(
+        "SELECT difficulty, seed, generator_version, challenge_parameters FROM applicants"
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .expect("Failed to fetch saved applicant.");
+
+    assert_eq!(saved.difficulty, "hard");
+    assert_eq!(saved.challenge_parameters["witnesses_per_bug"], 3);
+    assert_eq!(saved.challenge_parameters["num_digits"]["min"], 64);
+
+    let regenerated = ChallengeKindName::Barcode
+        .regenerate_challenge(
+            saved.seed.unwrap() as u64,
+            saved.generator_version.unwrap(),
+            &saved.challenge_parameters,
+        )
+        .expect("Failed to regenerate challenge.");
+
+    assert_eq!(regenerated.challenge, response.challenge);
+}
+
+#[tokio::test]
+async fn register_falls_back_to_the_configured_difficulty() {
+    let app = spawn_app_with(|c| c.challenge.default_difficulty = Difficulty::Easy).await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: RegisterResponseData = response.json().await.unwrap();
+
+    assert_eq!(Difficulty::Easy, response.difficulty);
+
+    let saved = sqlx::query!("SELECT difficulty, challenge_parameters FROM applicants")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved applicant.");
+
+    assert_eq!(saved.difficulty, "easy");
+    assert_eq!(saved.challenge_parameters["num_digits"]["max"], 16);
+    // Fields the tier leaves alone come from the base parameters.
+    assert_eq!(saved.challenge_parameters["num_random_cases"], 256);
+}
+
+#[tokio::test]
+async fn register_returns_a_400_for_an_unknown_difficulty() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/v1/register", &app.address))
+        .json(&hashmap! {
+            "name" => "Garrett",
+            "nuid" => "001234567",
+            "email" => "garrett@northeastern.edu",
+            "difficulty" => "extreme",
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(400, response.status().as_u16());
+
+    let error = parse_error(response).await;
+    assert_eq!("validation_error", error.code);
+    assert_eq!("Invalid difficulty! Given: extreme", error.message);
+}
+
+#[tokio::test]
+async fn register_returns_a_400_for_a_difficulty_applicants_may_not_choose() {
+    let app =
+        spawn_app_with(|c| c.challenge.selectable_difficulties = vec![Difficulty::Hard]).await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/v1/register", &app.address))
+        .json(&hashmap! {
+            "name" => "Garrett",
+            "nuid" => "001234567",
+            "email" => "garrett@northeastern.edu",
+            "difficulty" => "easy",
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(400, response.status().as_u16());
+
+    let error = parse_error(response).await;
+    assert_eq!("validation_error", error.code);
+    assert_eq!("Difficulty cannot be chosen! Given: easy", error.message);
+}
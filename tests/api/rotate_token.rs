@@ -0,0 +1,207 @@
+use generate_coding_challenge_server::routes::admin::token::RevokeTokenResponseData;
+use reqwest::Response;
+use serde_json::Value;
+
+use crate::helpers::{parse_error, spawn_app, TestApp};
+
+async fn rotate(app: &TestApp, client: &reqwest::Client, token: &str) -> Response {
+    client
+        .post(format!("{}/v1/rotate_token/{}", &app.address, token))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn get_challenge(app: &TestApp, client: &reqwest::Client, token: &str) -> Response {
+    client
+        .get(format!("{}/v1/challenge/{}", &app.address, token))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn rotate_token_issues_a_new_token_and_revokes_the_old_one() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let old_token = app.register_with_nuid(&client, "001234567").await.token;
+
+    let response = rotate(&app, &client, &old_token).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: Value = response.json().await.unwrap();
+    let new_token = body["token"].as_str().unwrap().to_string();
+
+    assert_ne!(old_token, new_token);
+    assert_eq!(
+        200,
+        get_challenge(&app, &client, &new_token)
+            .await
+            .status()
+            .as_u16()
+    );
+
+    let response = get_challenge(&app, &client, &old_token).await;
+
+    assert_eq!(401, response.status().as_u16());
+
+    let error = parse_error(response).await;
+    assert_eq!("token_revoked", error.code);
+    assert!(error.message.contains("forgot-token"));
+
+    let response = client
+        .post(format!("{}/v1/submit/{}", &app.address, &old_token))
+        .json(&serde_json::json!([]))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+
+    let saved = sqlx::query!("SELECT nuid, revoked_by, admin_key_hash FROM revoked_tokens")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch revoked token.");
+
+    assert_eq!(saved.nuid, "001234567");
+    assert_eq!(saved.revoked_by, "applicant");
+    assert!(saved.admin_key_hash.is_none());
+}
+
+#[tokio::test]
+async fn rotate_token_returns_a_401_for_a_revoked_token() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let token = app.register_with_nuid(&client, "001234567").await.token;
+
+    assert_eq!(200, rotate(&app, &client, &token).await.status().as_u16());
+
+    let response = rotate(&app, &client, &token).await;
+
+    assert_eq!(401, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn rotate_token_returns_a_404_for_an_unknown_token() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = rotate(&app, &client, "67e55044-10b1-426f-9247-bb680e5fe0c8").await;
+
+    assert_eq!(404, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn admins_can_revoke_an_applicants_token() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let old_token = app.register_with_nuid(&client, "001234567").await.token;
+
+    let response = client
+        .post(format!(
+            "{}/v1/admin/applicants/001234567/rotate_token",
+            &app.address
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: Value = response.json().await.unwrap();
+    assert!(body.get("token").is_none());
+    let body: RevokeTokenResponseData = serde_json::from_value(body).unwrap();
+
+    let response = get_challenge(&app, &client, &old_token).await;
+
+    assert_eq!(401, response.status().as_u16());
+
+    let saved = sqlx::query!("SELECT revoked_by, admin_key_hash, revoked_at FROM revoked_tokens")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch revoked token.");
+
+    assert_eq!(saved.revoked_by, "admin");
+    assert!(saved.admin_key_hash.is_some());
+    assert_eq!(saved.revoked_at, body.revoked_at);
+}
+
+#[tokio::test]
+async fn admin_rotate_token_returns_a_409_without_an_email_on_file() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let token = app.register_with_nuid(&client, "001234567").await.token;
+
+    // Applicants registered before emails were collected have none on file.
+    sqlx::query!("UPDATE applicants SET email = NULL")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to clear email.");
+
+    let response = client
+        .post(format!(
+            "{}/v1/admin/applicants/001234567/rotate_token",
+            &app.address
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(409, response.status().as_u16());
+    assert_eq!("conflict", parse_error(response).await.code);
+
+    let response = get_challenge(&app, &client, &token).await;
+
+    assert_eq!(200, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn admin_rotate_token_returns_a_404_for_an_unknown_nuid() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!(
+            "{}/v1/admin/applicants/001234567/rotate_token",
+            &app.address
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(404, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn admin_rotate_token_requires_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    app.register_with_nuid(&client, "001234567").await;
+
+    let response = client
+        .post(format!(
+            "{}/v1/admin/applicants/001234567/rotate_token",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
@@ -0,0 +1,152 @@
+use generate_coding_challenge_server::routes::admin::stats::StatsResponseData;
+use reqwest::Response;
+
+use crate::helpers::{parse_error, solve, spawn_app, TestApp};
+
+async fn get_stats(app: &TestApp, client: &reqwest::Client, query: &str) -> Response {
+    client
+        .get(format!("{}/v1/admin/stats?{}", &app.address, query))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+/// Registers an applicant and submits each answer in turn, where `true` is
+/// the correct solution and `false` a wrong one.
+async fn register_and_submit(
+    app: &TestApp,
+    client: &reqwest::Client,
+    nuid: &str,
+    answers: &[bool],
+) {
+    let registration = app.register_with_nuid(client, nuid).await;
+    let solution = solve(&registration);
+
+    for correct in answers {
+        let answer: &[String] = if *correct { &solution } else { &[] };
+        app.submit(client, &registration.token, answer).await;
+    }
+}
+
+#[tokio::test]
+async fn stats_summarizes_the_cohort() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_and_submit(&app, &client, "000000001", &[false, true]).await;
+    register_and_submit(&app, &client, "000000002", &[true]).await;
+    register_and_submit(&app, &client, "000000003", &[false]).await;
+    register_and_submit(&app, &client, "000000004", &[]).await;
+
+    let response = get_stats(&app, &client, "").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let stats: StatsResponseData = response.json().await.unwrap();
+
+    assert_eq!(4, stats.registrations);
+    assert_eq!(3, stats.submitted);
+    assert_eq!(2, stats.correct);
+    assert_eq!(0.75, stats.submission_rate);
+    assert_eq!(2.0 / 3.0, stats.correctness_rate);
+    assert!(stats.median_time_to_completion_seconds.is_some());
+    assert!(stats.p90_time_to_completion_seconds >= stats.median_time_to_completion_seconds);
+
+    assert_eq!(
+        4,
+        stats
+            .registrations_over_time
+            .iter()
+            .map(|period| period.registrations)
+            .sum::<i64>()
+    );
+
+    let distribution = stats
+        .attempts_to_first_correct
+        .iter()
+        .map(|bucket| (bucket.attempts, bucket.applicants))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![(1, 1), (2, 1)], distribution);
+
+    let leaderboard = stats
+        .leaderboard
+        .iter()
+        .map(|entry| (entry.rank, entry.nuid.as_str(), entry.correct))
+        .collect::<Vec<_>>();
+    assert_eq!(3, leaderboard.len());
+    assert!(leaderboard[0].2 && leaderboard[1].2);
+    assert_eq!((3, "000000003", false), leaderboard[2]);
+}
+
+#[tokio::test]
+async fn stats_limits_the_leaderboard() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_and_submit(&app, &client, "000000001", &[true]).await;
+    register_and_submit(&app, &client, "000000002", &[false]).await;
+
+    let response = get_stats(&app, &client, "leaderboard_size=1&interval=week").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let stats: StatsResponseData = response.json().await.unwrap();
+    assert_eq!(1, stats.leaderboard.len());
+    assert_eq!("000000001", stats.leaderboard[0].nuid);
+    assert_eq!(1, stats.registrations_over_time.len());
+}
+
+#[tokio::test]
+async fn stats_handles_an_empty_cohort() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = get_stats(&app, &client, "").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let stats: StatsResponseData = response.json().await.unwrap();
+    assert_eq!(0, stats.registrations);
+    assert_eq!(0.0, stats.submission_rate);
+    assert_eq!(0.0, stats.correctness_rate);
+    assert_eq!(None, stats.median_time_to_completion_seconds);
+    assert!(stats.leaderboard.is_empty());
+}
+
+#[tokio::test]
+async fn stats_returns_a_400_for_invalid_parameters() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    for query in ["interval=month", "scoring=median", "leaderboard_size=101"] {
+        let response = get_stats(&app, &client, query).await;
+
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not fail with 400 Bad Request when given {}.",
+            query
+        );
+        assert_eq!("validation_error", parse_error(response).await.code);
+    }
+}
+
+#[tokio::test]
+async fn stats_requires_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/v1/admin/stats", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
@@ -0,0 +1,151 @@
+use generate_coding_challenge_server::domain::algo_question::BarcodeBug;
+use generate_coding_challenge_server::routes::admin::applicants::ListApplicantsResponseData;
+use generate_coding_challenge_server::routes::admin::submissions::SubmissionHistoryResponseData;
+use generate_coding_challenge_server::routes::RegisterResponseData;
+use reqwest::Response;
+
+use crate::helpers::{parse_error, register_sample_applicant, solve, spawn_app, TestApp};
+
+async fn get_submissions(app: &TestApp, client: &reqwest::Client, nuid: &str) -> Response {
+    client
+        .get(format!(
+            "{}/v1/admin/applicants/{}/submissions",
+            &app.address, nuid
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn submission_history_returns_every_attempt_oldest_first() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let registration = app.register_with_nuid(&client, "001234567").await;
+
+    let solution = solve(&registration);
+    let wrong_answer = vec!["wrong".to_string()];
+
+    app.submit(&client, &registration.token, &wrong_answer)
+        .await;
+    app.submit(&client, &registration.token, &solution).await;
+
+    let response = get_submissions(&app, &client, "001234567").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let history: SubmissionHistoryResponseData = response.json().await.unwrap();
+
+    assert_eq!("001234567", history.nuid);
+    assert_eq!(2, history.submissions.len());
+
+    let first = &history.submissions[0];
+    assert!(!first.correct);
+    assert_eq!(Some(wrong_answer), first.answer);
+    assert!(!first.late);
+
+    let second = &history.submissions[1];
+    assert!(second.correct);
+    assert_eq!(Some(solution), second.answer);
+    assert!(first.submission_time <= second.submission_time);
+}
+
+#[tokio::test]
+async fn incorrect_submissions_are_diagnosed_for_reviewers_only() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+    let registration: RegisterResponseData = response.json().await.unwrap();
+
+    let buggy_answer = registration
+        .challenge
+        .iter()
+        .map(|case| BarcodeBug::EncryptWithoutModulo.parse(case))
+        .collect::<Vec<String>>();
+    let wrong_answer = vec!["wrong".to_string(); registration.challenge.len()];
+
+    for answer in [&buggy_answer, &wrong_answer] {
+        let response = client
+            .post(format!(
+                "{}/v1/submit/{}",
+                &app.address, &registration.token
+            ))
+            .json(answer)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(200, response.status().as_u16());
+
+        let body = response.text().await.unwrap();
+        assert!(!body.contains("misconception"));
+    }
+
+    let response = get_submissions(&app, &client, "001234567").await;
+    let history: SubmissionHistoryResponseData = response.json().await.unwrap();
+
+    assert_eq!(
+        Some("encrypt_without_modulo".to_string()),
+        history.submissions[0].misconception
+    );
+    assert_eq!(None, history.submissions[1].misconception);
+
+    let response = app.list_applicants(&client, "scoring=best_score").await;
+    let listing: ListApplicantsResponseData = response.json().await.unwrap();
+
+    assert_eq!(
+        Some("encrypt_without_modulo".to_string()),
+        listing.applicants[0].misconception
+    );
+}
+
+#[tokio::test]
+async fn submission_history_is_empty_before_any_submission() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_sample_applicant(&client, &app.address).await;
+
+    let response = get_submissions(&app, &client, "001234567").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let history: SubmissionHistoryResponseData = response.json().await.unwrap();
+    assert!(history.submissions.is_empty());
+}
+
+#[tokio::test]
+async fn submission_history_returns_a_404_for_an_unknown_nuid() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = get_submissions(&app, &client, "001234567").await;
+
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!("not_found", parse_error(response).await.code);
+}
+
+#[tokio::test]
+async fn submission_history_requires_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/v1/admin/applicants/001234567/submissions",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
@@ -1,10 +1,12 @@
 use maplit::hashmap;
 use serde_json::Value;
 
-use crate::helpers::{register_sample_applicant, spawn_app};
+use crate::helpers::{parse_error, register_sample_applicant, solve, spawn_app, spawn_app_with};
 use generate_coding_challenge_server::{
     domain::algo_question::parse_barcode,
-    routes::{RegisterResponseData, SubmitResponseData},
+    routes::{
+        admin::applicants::ListApplicantsResponseData, RegisterResponseData, SubmitResponseData,
+    },
 };
 
 #[tokio::test]
@@ -41,7 +43,7 @@ async fn submit_returns_a_200_for_correct_solution() {
     );
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -57,14 +59,79 @@ async fn submit_returns_a_200_for_correct_solution() {
 
     assert!(correct);
     assert_eq!("Correct - nice work!", message);
+    assert_eq!(response.cases_passed, challenge.len());
+    assert_eq!(response.cases_total, challenge.len());
+    assert!(response.failed_cases.is_empty());
+    assert_eq!(response.score, 1.0);
 
-    let saved = sqlx::query!("SELECT nuid, correct FROM submissions",)
+    let saved = sqlx::query!("SELECT nuid, correct, cases_passed, score FROM submissions",)
         .fetch_one(&app.db_pool)
         .await
         .expect("Failed to fetch saved applicant.");
 
     assert_eq!(saved.nuid, "001234567");
     assert!(saved.correct);
+    assert_eq!(saved.cases_passed, Some(challenge.len() as i32));
+    assert_eq!(saved.score, Some(1.0));
+}
+
+#[tokio::test]
+async fn submit_reports_failed_cases_for_a_partially_correct_solution() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    let response: RegisterResponseData =
+        serde_json::from_str(&register_response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    let mut solution = response
+        .challenge
+        .iter()
+        .map(|case| parse_barcode(case))
+        .collect::<Vec<String>>();
+
+    let wrong_cases = vec![0, 7];
+    for &i in &wrong_cases {
+        solution[i] = format!("{}x", solution[i]);
+    }
+
+    let response = client
+        .post(format!("{}/v1/submit/{}", &app.address, &response.token))
+        .json(&solution)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: SubmitResponseData = serde_json::from_str(&response.text().await.unwrap())
+        .expect("Failed to parse response JSON");
+
+    assert!(!response.correct);
+    assert_eq!(response.cases_passed, solution.len() - wrong_cases.len());
+    assert_eq!(response.cases_total, solution.len());
+    assert_eq!(response.failed_cases, wrong_cases);
+
+    let saved = sqlx::query!(
+        "SELECT correct, cases_passed, cases_total, failed_cases, score FROM submissions",
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .expect("Failed to fetch saved submission.");
+
+    assert!(!saved.correct);
+    assert_eq!(
+        saved.cases_passed,
+        Some((solution.len() - wrong_cases.len()) as i32)
+    );
+    assert_eq!(saved.cases_total, Some(solution.len() as i32));
+    assert_eq!(saved.failed_cases, Some(vec![0, 7]));
+    assert!((saved.score.unwrap() - response.score).abs() < f64::EPSILON * 4.0);
 }
 
 #[tokio::test]
@@ -85,7 +152,7 @@ async fn submit_returns_a_200_for_incorrect_solution() {
     let solution_json: serde_json::Value = serde_json::Value::Array(Vec::new());
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -101,6 +168,9 @@ async fn submit_returns_a_200_for_incorrect_solution() {
 
     assert!(!correct);
     assert_eq!("Incorrect Solution", message);
+    assert_eq!(response.cases_passed, 0);
+    assert_eq!(response.failed_cases.len(), response.cases_total);
+    assert_eq!(response.score, 0.0);
 
     let saved = sqlx::query!("SELECT nuid, correct FROM submissions",)
         .fetch_one(&app.db_pool)
@@ -128,7 +198,7 @@ async fn submit_returns_a_400_for_bad_request() {
     let token = response.token;
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&hashmap! {
             "name" => "Garrett",
             "nuid" => "001234567",
@@ -138,6 +208,7 @@ async fn submit_returns_a_400_for_bad_request() {
         .expect("Failed to execute request.");
 
     assert_eq!(400, response.status().as_u16());
+    assert_eq!("validation_error", parse_error(response).await.code);
 }
 
 #[tokio::test]
@@ -151,7 +222,7 @@ async fn submit_returns_a_404_for_user_that_does_not_exist() {
     let bad_token = "67e55044-10b1-426f-9247-bb680e5fe0c8";
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &bad_token))
+        .post(format!("{}/v1/submit/{}", &app.address, &bad_token))
         .json(&solution_json)
         .send()
         .await
@@ -159,12 +230,14 @@ async fn submit_returns_a_404_for_user_that_does_not_exist() {
 
     assert_eq!(404, response.status().as_u16());
 
+    let error = parse_error(response).await;
+    assert_eq!("not_found", error.code);
     assert_eq!(
         format!(
             "Record associated with given token not found! Token: {}",
             &bad_token
         ),
-        response.text().await.unwrap()
+        error.message
     );
 }
 
@@ -202,7 +275,7 @@ async fn submit_correct_then_incorrect_results_in_incorrect() {
     );
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -230,7 +303,7 @@ async fn submit_correct_then_incorrect_results_in_incorrect() {
     let solution_json: serde_json::Value = serde_json::Value::Array(Vec::new());
 
     let response = client
-        .post(&format!("{}/submit/{}", &app.address, &token))
+        .post(format!("{}/v1/submit/{}", &app.address, &token))
         .json(&solution_json)
         .send()
         .await
@@ -258,3 +331,233 @@ async fn submit_correct_then_incorrect_results_in_incorrect() {
     assert_eq!(most_recent_sub.nuid, "001234567");
     assert!(!most_recent_sub.correct);
 }
+
+#[tokio::test]
+async fn submit_returns_remaining_attempts_then_a_403_once_exhausted() {
+    let app = spawn_app_with(|c| {
+        c.submission.max_attempts = Some(2);
+        c.submission.min_interval_seconds = 0;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    let response: RegisterResponseData =
+        serde_json::from_str(&register_response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    let solution_json: serde_json::Value = serde_json::Value::Array(Vec::new());
+
+    for expected_remaining in [1, 0] {
+        let response = client
+            .post(format!("{}/v1/submit/{}", &app.address, &response.token))
+            .json(&solution_json)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(200, response.status().as_u16());
+
+        let response: SubmitResponseData = serde_json::from_str(&response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+        assert_eq!(response.remaining_attempts, Some(expected_remaining));
+    }
+
+    let response = client
+        .post(format!("{}/v1/submit/{}", &app.address, &response.token))
+        .json(&solution_json)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Exhaustion is permanent, so there is nothing to retry after.
+    assert_eq!(403, response.status().as_u16());
+    assert!(response.headers().get("Retry-After").is_none());
+    let error = parse_error(response).await;
+    assert_eq!("attempts_exhausted", error.code);
+    assert_eq!("No submission attempts remaining!", error.message);
+
+    let saved = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM submissions"#,)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to count submissions.");
+
+    assert_eq!(saved.count, 2);
+}
+
+#[tokio::test]
+async fn submit_returns_a_429_with_retry_after_during_the_cooldown() {
+    let app = spawn_app_with(|c| {
+        c.submission.max_attempts = None;
+        c.submission.min_interval_seconds = 600;
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    let response: RegisterResponseData =
+        serde_json::from_str(&register_response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    let solution_json: serde_json::Value = serde_json::Value::Array(Vec::new());
+
+    let first = client
+        .post(format!("{}/v1/submit/{}", &app.address, &response.token))
+        .json(&solution_json)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, first.status().as_u16());
+
+    let first: SubmitResponseData =
+        serde_json::from_str(&first.text().await.unwrap()).expect("Failed to parse response JSON");
+
+    assert_eq!(first.remaining_attempts, None);
+
+    let second = client
+        .post(format!("{}/v1/submit/{}", &app.address, &response.token))
+        .json(&solution_json)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(429, second.status().as_u16());
+
+    let retry_after: u64 = second.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .expect("Retry-After was not a number of seconds.");
+
+    assert!(retry_after > 0 && retry_after <= 600);
+
+    let error = parse_error(second).await;
+    assert_eq!("submission_cooldown", error.code);
+    assert_eq!(
+        retry_after,
+        error.details.unwrap()["retry_after_seconds"]
+            .as_u64()
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn submit_returns_a_410_after_the_deadline() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let register_response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, register_response.status().as_u16());
+
+    let response: RegisterResponseData =
+        serde_json::from_str(&register_response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    sqlx::query!("UPDATE applicants SET deadline = now() - interval '1 minute'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to expire applicant.");
+
+    let response = client
+        .post(format!("{}/v1/submit/{}", &app.address, &response.token))
+        .json(&serde_json::Value::Array(Vec::new()))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(410, response.status().as_u16());
+
+    let saved = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM submissions"#,)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to count submissions.");
+
+    assert_eq!(saved.count, 0);
+}
+
+#[tokio::test]
+async fn submit_accepts_late_submissions_during_the_grace_period() {
+    let app = spawn_app_with(|c| c.challenge.late_grace_minutes = 30).await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+    let registration: RegisterResponseData = response.json().await.unwrap();
+
+    sqlx::query!("UPDATE applicants SET deadline = now() - interval '1 minute'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to expire applicant.");
+
+    let response = client
+        .post(format!(
+            "{}/v1/submit/{}",
+            &app.address, &registration.token
+        ))
+        .json(&serde_json::Value::Array(Vec::new()))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response: SubmitResponseData = response.json().await.unwrap();
+    assert!(response.late);
+
+    let response = app.list_applicants(&client, "").await;
+    let listing: ListApplicantsResponseData = response.json().await.unwrap();
+    assert_eq!(Some(true), listing.applicants[0].late);
+
+    // Past the grace period submissions are rejected.
+    sqlx::query!("UPDATE applicants SET deadline = now() - interval '31 minutes'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to expire applicant.");
+
+    let response = client
+        .post(format!(
+            "{}/v1/submit/{}",
+            &app.address, &registration.token
+        ))
+        .json(&serde_json::Value::Array(Vec::new()))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(410, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn submit_grades_extended_instructions_when_enabled() {
+    let app = spawn_app_with(|c| {
+        c.challenge.parameters["extended_instructions"] = serde_json::json!(true);
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+
+    let registration = app.register_with_nuid(&client, "001234567").await;
+
+    assert!(registration.prompt.contains("'$'"));
+    assert!(registration
+        .challenge
+        .iter()
+        .any(|case| case.chars().any(|c| ">~-$".contains(c))));
+
+    let response = app
+        .submit(&client, &registration.token, &solve(&registration))
+        .await;
+    assert!(response.correct);
+}
@@ -0,0 +1,116 @@
+use generate_coding_challenge_server::domain::parse_barcode;
+use generate_coding_challenge_server::routes::admin::trace::CaseTraceResponseData;
+use generate_coding_challenge_server::routes::RegisterResponseData;
+use reqwest::Response;
+
+use crate::helpers::{parse_error, register_sample_applicant, spawn_app, TestApp};
+
+async fn get_trace(app: &TestApp, client: &reqwest::Client, nuid: &str, case: &str) -> Response {
+    client
+        .get(format!(
+            "{}/v1/admin/applicants/{}/cases/{}/trace",
+            &app.address, nuid, case
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn trace_follows_a_case_of_the_applicants_challenge() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+    let registration: RegisterResponseData = response.json().await.unwrap();
+
+    // Cases are shuffled, and the empty mandatory case has no steps to follow.
+    let case = registration
+        .challenge
+        .iter()
+        .position(|barcode| !barcode.is_empty())
+        .unwrap();
+
+    let response = get_trace(&app, &client, "001234567", &case.to_string()).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let trace: CaseTraceResponseData = response.json().await.unwrap();
+
+    assert_eq!(case, trace.case);
+    assert_eq!(registration.challenge[case], trace.barcode);
+    assert_eq!(parse_barcode(&trace.barcode), trace.expected);
+    assert_eq!(trace.barcode.chars().count(), trace.steps.len());
+
+    let last = trace.steps.last().unwrap();
+    assert_eq!(trace.expected, last.result.concat() + &last.current_block);
+}
+
+#[tokio::test]
+async fn trace_returns_a_404_for_a_case_past_the_end() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+    let registration: RegisterResponseData = response.json().await.unwrap();
+
+    let response = get_trace(
+        &app,
+        &client,
+        "001234567",
+        &registration.challenge.len().to_string(),
+    )
+    .await;
+
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!("not_found", parse_error(response).await.code);
+}
+
+#[tokio::test]
+async fn trace_returns_a_404_for_an_unknown_nuid() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = get_trace(&app, &client, "001234567", "0").await;
+
+    assert_eq!(404, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn trace_returns_a_400_for_an_invalid_case_index() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_sample_applicant(&client, &app.address).await;
+
+    let response = get_trace(&app, &client, "001234567", "first").await;
+
+    assert_eq!(400, response.status().as_u16());
+
+    let error = parse_error(response).await;
+    assert_eq!("validation_error", error.code);
+    assert_eq!("Invalid case index! Given: first", error.message);
+}
+
+#[tokio::test]
+async fn trace_requires_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/v1/admin/applicants/001234567/cases/0/trace",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
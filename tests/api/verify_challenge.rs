@@ -0,0 +1,107 @@
+use generate_coding_challenge_server::routes::admin::verify::VerifyChallengeResponseData;
+use reqwest::Response;
+
+use crate::helpers::{parse_error, register_sample_applicant, spawn_app, TestApp};
+
+async fn get_verify(app: &TestApp, client: &reqwest::Client, nuid: &str) -> Response {
+    client
+        .get(format!(
+            "{}/v1/admin/applicants/{}/challenge/verify",
+            &app.address, nuid
+        ))
+        .bearer_auth(&app.admin_api_key)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn verify_reproduces_a_freshly_issued_challenge() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_sample_applicant(&client, &app.address).await;
+
+    let response = get_verify(&app, &client, "001234567").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let verification: VerifyChallengeResponseData = response.json().await.unwrap();
+
+    assert_eq!("001234567", verification.nuid);
+    assert!(verification.matches);
+    assert!(verification.mismatched_cases.is_empty());
+}
+
+#[tokio::test]
+async fn verify_reports_cases_that_differ_from_the_regenerated_challenge() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_sample_applicant(&client, &app.address).await;
+
+    sqlx::query!("UPDATE applicants SET solution[2] = 'tampered' WHERE nuid='001234567'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to tamper with the stored solution.");
+
+    let response = get_verify(&app, &client, "001234567").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let verification: VerifyChallengeResponseData = response.json().await.unwrap();
+
+    assert!(!verification.matches);
+    // Postgres arrays are 1-indexed.
+    assert_eq!(vec![1], verification.mismatched_cases);
+}
+
+#[tokio::test]
+async fn verify_returns_a_409_for_a_challenge_without_a_seed() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_sample_applicant(&client, &app.address).await;
+
+    sqlx::query!("UPDATE applicants SET seed = NULL WHERE nuid='001234567'")
+        .execute(&app.db_pool)
+        .await
+        .expect("Failed to clear the stored seed.");
+
+    let response = get_verify(&app, &client, "001234567").await;
+
+    assert_eq!(409, response.status().as_u16());
+    assert_eq!("conflict", parse_error(response).await.code);
+}
+
+#[tokio::test]
+async fn verify_returns_a_404_for_an_unknown_nuid() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = get_verify(&app, &client, "001234567").await;
+
+    assert_eq!(404, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn verify_requires_an_admin_api_key() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/v1/admin/applicants/001234567/challenge/verify",
+            &app.address
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status().as_u16());
+}
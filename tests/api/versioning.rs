@@ -0,0 +1,191 @@
+use crate::helpers::{parse_error, register_sample_applicant, solve, spawn_app};
+use chrono::Duration;
+use generate_coding_challenge_server::routes::{RegisterResponseData, SubmitResponseData};
+use maplit::hashmap;
+use reqwest::Response;
+
+#[tokio::test]
+async fn v1_routes_are_not_deprecated() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert!(response.headers().get("Deprecation").is_none());
+}
+
+#[tokio::test]
+async fn root_aliases_still_work_but_are_deprecated() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = register_sample_applicant(&client, &app.address).await;
+
+    let register_response: RegisterResponseData =
+        serde_json::from_str(&response.text().await.unwrap())
+            .expect("Failed to parse response JSON");
+
+    let response = client
+        .get(format!(
+            "{}/challenge/{}",
+            &app.address, &register_response.token
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!("true", response.headers().get("Deprecation").unwrap());
+    assert_eq!(
+        r#"</v1>; rel="successor-version""#,
+        response.headers().get("Link").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn root_alias_errors_are_deprecated_too() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/forgot_token/{}", &app.address, "a".repeat(9)))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!("true", response.headers().get("Deprecation").unwrap());
+}
+
+#[tokio::test]
+async fn the_old_forgot_token_get_points_at_the_new_flow() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    register_sample_applicant(&client, &app.address).await;
+
+    let response = client
+        .get(format!("{}/forgot_token/{}", &app.address, "001234567"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(410, response.status().as_u16());
+    assert_eq!("true", response.headers().get("Deprecation").unwrap());
+
+    let error = parse_error(response).await;
+    assert_eq!("gone", error.code);
+    assert!(error.message.contains("/v1/forgot_token/001234567"));
+    assert!(app.mailer.sent().is_empty());
+}
+
+#[tokio::test]
+async fn unversioned_routes_are_not_deprecated() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/health_check", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+    assert!(response.headers().get("Deprecation").is_none());
+}
+
+/// Asserts `response` succeeded, and is deprecated exactly when it was served
+/// from the root aliases.
+fn assert_served(prefix: &str, response: &Response) {
+    assert!(
+        response.status().is_success(),
+        "{} failed under {:?}.",
+        response.url().path(),
+        prefix
+    );
+    assert_eq!(
+        prefix.is_empty(),
+        response.headers().get("Deprecation").is_some(),
+        "Unexpected deprecation of {}.",
+        response.url().path()
+    );
+}
+
+#[tokio::test]
+async fn the_original_calls_work_under_both_prefixes() {
+    let app = spawn_app().await;
+
+    let client = reqwest::Client::new();
+
+    for (prefix, nuid) in [("", "001234567"), ("/v1", "007654321")] {
+        let response = client
+            .post(format!("{}{}/register", &app.address, prefix))
+            .json(&hashmap! {
+                "name" => "Garrett",
+                "nuid" => nuid,
+                "email" => "garrett@northeastern.edu",
+            })
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_served(prefix, &response);
+        let registration: RegisterResponseData = response.json().await.unwrap();
+
+        let response = client
+            .get(format!(
+                "{}{}/challenge/{}",
+                &app.address, prefix, &registration.token
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_served(prefix, &response);
+
+        let response = client
+            .post(format!(
+                "{}{}/submit/{}",
+                &app.address, prefix, &registration.token
+            ))
+            .json(&solve(&registration))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_served(prefix, &response);
+        let submission: SubmitResponseData = response.json().await.unwrap();
+        assert!(submission.correct);
+
+        let response = client
+            .get(format!("{}{}/applicants", &app.address, prefix))
+            .bearer_auth(&app.admin_api_key)
+            .json(&serde_json::json!([nuid]))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_served(prefix, &response);
+
+        let response = client
+            .put(format!(
+                "{}{}/admin/applicants/{}/deadline",
+                &app.address, prefix, nuid
+            ))
+            .bearer_auth(&app.admin_api_key)
+            .json(&serde_json::json!({ "deadline": registration.deadline + Duration::days(1) }))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_served(prefix, &response);
+
+        let response = client
+            .post(format!("{}{}/forgot_token/{}", &app.address, prefix, nuid))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_served(prefix, &response);
+    }
+}